@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use cursive::{Printer, Vec2, XY};
+use teletarot_model::{Board, Card};
+
+use crate::{xp::CardBorderColor, TeletarotView};
+
+/// How many refreshes it takes a card to slide to where it's going.
+pub const FRAMES_PER_STEP: usize = 6;
+/// How often the screen refreshes while animating or autoplaying.
+pub const ANIMATION_FPS: u32 = 30;
+/// How often it refreshes otherwise, which is enough for the clock.
+pub const IDLE_FPS: u32 = 2;
+
+/// The cards that moved in one step of the game, sliding from where they
+/// were to where they ended up.
+#[derive(Debug, Clone)]
+pub struct Animation {
+  /// The board as it is once this step is done. Cards in flight are drawn
+  /// flying instead of in their place on this board.
+  board: Board,
+  flights: Vec<Flight>,
+  frame: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Flight {
+  card: Card,
+  from: Vec2,
+  to: Vec2,
+}
+
+impl Animation {
+  pub fn board(&self) -> &Board {
+    &self.board
+  }
+
+  pub fn is_flying(&self, card: &Card) -> bool {
+    self.flights.iter().any(|flight| &flight.card == card)
+  }
+}
+
+impl Flight {
+  /// Where the card is partway through the flight.
+  fn pos_at(&self, frame: usize) -> Vec2 {
+    // Ease out, so cards settle into place instead of slamming into it
+    let t = (frame + 1) as f32 / FRAMES_PER_STEP as f32;
+    let t = 1.0 - (1.0 - t) * (1.0 - t);
+    let lerp = |from: usize, to: usize| {
+      (from as f32 + (to as f32 - from as f32) * t).round() as usize
+    };
+    XY::new(lerp(self.from.x, self.to.x), lerp(self.from.y, self.to.y))
+  }
+}
+
+impl TeletarotView {
+  /// The board to draw: partway through the animations if any are playing.
+  pub fn shown_board(&self) -> &Board {
+    match self.animations.front() {
+      Some(anim) => anim.board(),
+      None => self.board(),
+    }
+  }
+
+  /// Animate the cards that moved going from `before` to the current board.
+  ///
+  /// Animations queue up, so call this after every step of a chain of moves
+  /// to have each step play one after another.
+  pub fn queue_animation(&mut self, before: &Board) {
+    if !self.animate {
+      return;
+    }
+
+    let from = self.card_positions(before);
+    let to = self.card_positions(self.board());
+    let flights = to
+      .into_iter()
+      .filter_map(|(card, to)| {
+        let from = *from.get(&card)?;
+        (from != to).then_some(Flight { card, from, to })
+      })
+      .collect::<Vec<_>>();
+    if flights.is_empty() {
+      return;
+    }
+
+    self.animations.push_back(Animation {
+      board: self.board().clone(),
+      flights,
+      frame: 0,
+    });
+  }
+
+  /// Jump to the end of whatever's animating.
+  pub fn skip_animations(&mut self) {
+    self.animations.clear();
+  }
+
+  /// Move the animation on by a frame.
+  pub fn advance_animation(&mut self) {
+    let Some(anim) = self.animations.front_mut() else {
+      return;
+    };
+    anim.frame += 1;
+    if anim.frame >= FRAMES_PER_STEP {
+      self.animations.pop_front();
+    }
+  }
+
+  /// Whether the card is being animated, and so shouldn't be drawn in place.
+  pub fn is_flying(&self, card: &Card) -> bool {
+    self
+      .animations
+      .front()
+      .is_some_and(|anim| anim.is_flying(card))
+  }
+
+  pub fn draw_flights(&self, printer: &Printer) {
+    let Some(anim) = self.animations.front() else {
+      return;
+    };
+    for flight in &anim.flights {
+      self.atlas.print_card(
+        &flight.card,
+        flight.pos_at(anim.frame),
+        printer,
+        false,
+        CardBorderColor::AlwaysHilight,
+      );
+    }
+  }
+
+  /// Where every card on the board gets drawn.
+  fn card_positions(&self, board: &Board) -> HashMap<Card, Vec2> {
+    let mut poses = HashMap::new();
+
+    for (col_idx, col) in board.columns().iter().enumerate() {
+      for (card_idx, card) in col.iter().enumerate() {
+        poses.insert(card.clone(), self.atlas.card_pos(col_idx, col, card_idx));
+      }
+    }
+    if let Some(card) = board.minor_foundation_storage() {
+      poses.insert(card.clone(), self.atlas.min_fndn_storage());
+    }
+    for (suit_idx, stack) in
+      board.virtual_cards_minor_foundation().iter().enumerate()
+    {
+      for card in stack {
+        poses.insert(card.clone(), self.atlas.min_fndn_poses()[suit_idx]);
+      }
+    }
+
+    let final_major = final_major(board);
+    for (idx, card) in board
+      .virtual_cards_major_foundation_left()
+      .into_iter()
+      .enumerate()
+    {
+      let is_final = Some(idx as u8) == final_major;
+      poses.insert(card, self.atlas.major_left_pos(idx, is_final));
+    }
+    for (idx, card) in board
+      .virtual_cards_major_foundation_right()
+      .into_iter()
+      .enumerate()
+    {
+      poses.insert(card, self.atlas.major_right_pos(idx));
+    }
+
+    poses
+  }
+}
+
+/// The card that closes the gap in the major foundation, if it's been placed.
+pub fn final_major(board: &Board) -> Option<u8> {
+  match (
+    board.major_foundation_left_max(),
+    board.major_foundation_right_min(),
+  ) {
+    (Some(left), Some(right)) if left + 1 == right => Some(left),
+    _ => None,
+  }
+}
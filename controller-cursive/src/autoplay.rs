@@ -0,0 +1,244 @@
+use std::{
+  collections::VecDeque,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc, Mutex,
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+use cursive::event::{Event, EventResult};
+use teletarot_model::{solver, BoardZone};
+
+use crate::{board_zone_to_idx, keymap::Action, TeletarotView};
+
+/// How long to wait between moves, at each speed.
+const STEP_DELAYS: [Duration; 5] = [
+  Duration::from_millis(2000),
+  Duration::from_millis(1000),
+  Duration::from_millis(500),
+  Duration::from_millis(250),
+  Duration::from_millis(100),
+];
+const DEFAULT_SPEED: usize = 2;
+
+type Solution = Vec<(BoardZone, BoardZone)>;
+
+/// The solver playing the game for the player to watch.
+pub struct Autoplay {
+  state: State,
+  /// Just show the player the next move instead of playing them all.
+  hint: bool,
+  paused: bool,
+  /// Index into [`STEP_DELAYS`].
+  speed: usize,
+  last_step: Instant,
+}
+
+enum State {
+  /// The solver's thinking about it in the background.
+  Solving {
+    /// In a mutex since views have to be `Sync`.
+    solution: Mutex<mpsc::Receiver<Option<Solution>>>,
+    /// Set to tell the solver to give up.
+    cancel: Arc<AtomicBool>,
+    started: Instant,
+  },
+  Playing {
+    moves: VecDeque<(BoardZone, BoardZone)>,
+    played: usize,
+  },
+}
+
+impl Autoplay {
+  /// A line saying what's going on, and what the keys do.
+  pub fn status_text(&self) -> String {
+    match &self.state {
+      State::Solving { started, .. } if self.hint => format!(
+        "Hint: thinking... {}s  (Esc to give up)",
+        started.elapsed().as_secs()
+      ),
+      State::Solving { started, .. } => format!(
+        "Autoplay: solving... {}s  (Esc to give up)",
+        started.elapsed().as_secs()
+      ),
+      State::Playing { moves, played } => format!(
+        "Autoplay {}/{}{}  Speed {}/{}  Space pause  . step  +/- speed  \
+         Esc stop",
+        played,
+        played + moves.len(),
+        if self.paused { " (paused)" } else { "" },
+        self.speed + 1,
+        STEP_DELAYS.len(),
+      ),
+    }
+  }
+}
+
+impl Drop for Autoplay {
+  fn drop(&mut self) {
+    // Don't leave the solver churning away for nobody
+    if let State::Solving { cancel, .. } = &self.state {
+      cancel.store(true, Ordering::Relaxed);
+    }
+  }
+}
+
+impl TeletarotView {
+  /// Have the solver play the rest of the game, starting from here.
+  pub fn start_autoplay(&mut self) {
+    self.start_solver(false);
+  }
+
+  /// Have the solver work out the next move, and pick it up for the player.
+  pub fn start_hint(&mut self) {
+    self.start_solver(true);
+  }
+
+  fn start_solver(&mut self, hint: bool) {
+    if self.won {
+      return;
+    }
+    self.cursor_src = None;
+    self.drag = None;
+    self.message = None;
+
+    let (tx, rx) = mpsc::channel();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let board = self.board().clone();
+    let thread_cancel = cancel.clone();
+    thread::spawn(move || {
+      let solution = solver::try_solve_while(&board, || {
+        !thread_cancel.load(Ordering::Relaxed)
+      });
+      // If nobody's listening anymore, that's fine
+      let _ = tx.send(solution);
+    });
+
+    self.autoplay = Some(Autoplay {
+      state: State::Solving {
+        solution: Mutex::new(rx),
+        cancel,
+        started: Instant::now(),
+      },
+      hint,
+      paused: false,
+      speed: DEFAULT_SPEED,
+      last_step: Instant::now(),
+    });
+  }
+
+  /// Keys for controlling the autoplay. Anything else is ignored so the
+  /// player can't play over the top of it, except for the global keys.
+  pub fn on_autoplay_event(&mut self, ev: Event) -> EventResult {
+    let Some(auto) = &mut self.autoplay else {
+      return EventResult::Ignored;
+    };
+    match (self.keymap.action(&ev), ev) {
+      (Some(Action::Cancel | Action::Autoplay), _) => {
+        self.autoplay = None;
+      }
+      (Some(Action::Select | Action::Pause), _) => {
+        auto.paused = !auto.paused;
+      }
+      (Some(Action::Compact), _) => self.toggle_compact(),
+      (Some(Action::AutoplayStep), _) => {
+        auto.paused = true;
+        return self.autoplay_step();
+      }
+      (Some(Action::AutoplayFaster), _) => {
+        auto.speed = (auto.speed + 1).min(STEP_DELAYS.len() - 1);
+      }
+      (Some(Action::AutoplaySlower), _) => {
+        auto.speed = auto.speed.saturating_sub(1);
+      }
+      (_, Event::Refresh) => return self.advance_autoplay(),
+      _ => return EventResult::Ignored,
+    }
+    EventResult::consumed()
+  }
+
+  /// Check on the solver, and play the next move if it's time to.
+  fn advance_autoplay(&mut self) -> EventResult {
+    let Some(auto) = &mut self.autoplay else {
+      return EventResult::Ignored;
+    };
+    let received = match &auto.state {
+      State::Solving { solution, .. } => solution.lock().unwrap().try_recv(),
+      State::Playing { .. } => {
+        let due = auto.last_step.elapsed() >= STEP_DELAYS[auto.speed];
+        if !auto.paused && due {
+          return self.autoplay_step();
+        }
+        return EventResult::Ignored;
+      }
+    };
+    match received {
+      Ok(Some(solution)) if auto.hint => {
+        self.game.mark_assisted();
+        self.autoplay = None;
+        self.automove();
+        self.message = Some(match solution.first() {
+          Some(&(src, dst)) => {
+            self.cursor_src = Some(board_zone_to_idx(src));
+            self.cursor = board_zone_to_idx(dst);
+            let card = self.moving_card().map(ToString::to_string);
+            format!("Hint: {} to {}", card.unwrap_or_default(), dst)
+          }
+          None => "Hint: there's nothing left to do.".to_string(),
+        });
+      }
+      Ok(Some(solution)) => {
+        self.game.mark_assisted();
+        auto.state = State::Playing {
+          moves: solution.into(),
+          played: 0,
+        };
+        auto.last_step = Instant::now();
+        // The solver starts from after the automoves
+        self.automove();
+      }
+      Ok(None) | Err(mpsc::TryRecvError::Disconnected) => {
+        self.autoplay = None;
+        self.message = Some("The solver couldn't find a way.".to_string());
+      }
+      Err(mpsc::TryRecvError::Empty) => {}
+    }
+    EventResult::Ignored
+  }
+
+  /// Play one move of the solution, once the last one's done moving.
+  fn autoplay_step(&mut self) -> EventResult {
+    if !self.animations.is_empty() {
+      return EventResult::consumed();
+    }
+    let Some(auto) = &mut self.autoplay else {
+      return EventResult::Ignored;
+    };
+    let State::Playing { moves, played } = &mut auto.state else {
+      return EventResult::consumed();
+    };
+    auto.last_step = Instant::now();
+    let Some((src, dst)) = moves.pop_front() else {
+      self.autoplay = None;
+      return EventResult::consumed();
+    };
+    *played += 1;
+
+    let before = self.board().clone();
+    if let Err(ono) = self.game.move_card(src, dst) {
+      self.autoplay = None;
+      self.message = Some(format!("The solver got it wrong: {}", ono));
+      return EventResult::consumed();
+    }
+    self.queue_animation(&before);
+    self.queue_automoves();
+
+    if self.game.is_solved() {
+      self.autoplay = None;
+      return self.win();
+    }
+    EventResult::consumed()
+  }
+}
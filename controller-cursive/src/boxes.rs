@@ -1,5 +1,5 @@
 use cursive::{theme::Style, utils::markup::StyledString, Printer, Vec2};
-use itertools::{iproduct, Itertools};
+use itertools::Itertools;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BoxSide {
@@ -33,7 +33,16 @@ impl BoxSide {
     }
   }
 
-  pub fn box_drawing_char(&self, thick: bool) -> char {
+  pub fn box_drawing_char(&self, thick: bool, ascii: bool) -> char {
+    if ascii {
+      return match (thick, self) {
+        (true, BoxSide::Horz) => '=',
+        (true, _) => '#',
+        (false, BoxSide::Horz) => '-',
+        (false, BoxSide::Vert) => '|',
+        (false, _) => '+',
+      };
+    }
     match (thick, self) {
       (true, BoxSide::NWCorner) => '╔',
       (true, BoxSide::Horz) => '═',
@@ -56,6 +65,7 @@ impl BoxSide {
     size: impl Into<Vec2>,
     style: Style,
     thick: bool,
+    ascii: bool,
   ) {
     let pos = pos.into();
     let size = size.into();
@@ -71,7 +81,7 @@ impl BoxSide {
       let here = pos + (dx, dy);
 
       let ch = BoxSide::from_delta_in_box((dx, dy).into(), size)
-        .box_drawing_char(thick);
+        .box_drawing_char(thick, ascii);
       printer.print_styled(here, &StyledString::styled(ch, style));
     }
   }
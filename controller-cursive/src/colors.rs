@@ -1,12 +1,315 @@
-use cursive::theme::BaseColor;
+use std::{fs, path::Path};
+
+use cursive::theme::{BaseColor, Color, Effect, Style};
+use serde::Deserialize;
 use teletarot_model::{MinorSuit, Suit};
 
-pub fn suit_color(suit: Suit) -> BaseColor {
-  match suit {
-    Suit::Minor(MinorSuit::Pentacles) => BaseColor::Yellow,
-    Suit::Minor(MinorSuit::Cups) => BaseColor::Red,
-    Suit::Minor(MinorSuit::Wands) => BaseColor::Green,
-    Suit::Minor(MinorSuit::Swords) => BaseColor::Cyan,
-    Suit::MajorArcana => BaseColor::Magenta,
+use crate::{fg_color, settings::Settings};
+
+/// The themes that come with the game, by name.
+pub const BUILTIN_THEMES: [&str; 4] =
+  ["dark", "light", "colorblind", "colorblind-light"];
+
+/// What colour everything gets drawn in.
+///
+/// Themes are TOML files in the `themes` folder of the config directory.
+/// Anything a theme leaves out comes from the built-in theme for its kind
+/// of terminal:
+///
+/// ```toml
+/// terminal = "light"
+/// background = "default"
+/// border = "white"
+/// highlight = "black"
+/// blocked = "light black"
+/// target = "blue"
+///
+/// [suits]
+/// pentacles = "yellow"
+/// cups = "red"
+/// swords = "cyan"
+/// wands = "green"
+/// major = "magenta"
+///
+/// [glyphs]
+/// pentacles = "◆"
+/// cups = "♥"
+/// swords = "♠"
+/// wands = "♣"
+/// major = "★"
+/// ```
+#[derive(Debug, Clone)]
+pub struct Palette {
+  pub terminal: Terminal,
+  /// Behind the board. `default` leaves the terminal's own background.
+  pub background: Color,
+  /// Empty slots and the lines on the table.
+  pub border: Color,
+  /// Cards being carried, and the selected storage.
+  pub highlight: Color,
+  /// The minor foundation while the storage over it is full.
+  pub blocked: Color,
+  /// Everywhere the selected card could go.
+  pub target: Color,
+  /// These get drawn dark or light depending on the terminal,
+  /// so they're just base colours.
+  pub suits: PerSuit<BaseColor>,
+  /// Marks to tell the suits apart without colour.
+  pub glyphs: Option<PerSuit<char>>,
+}
+
+/// Whether the terminal's background is dark or light.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Terminal {
+  Dark,
+  Light,
+}
+
+/// One of something for each suit.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PerSuit<T> {
+  pub pentacles: T,
+  pub cups: T,
+  pub swords: T,
+  pub wands: T,
+  pub major: T,
+}
+
+impl<T: Copy> PerSuit<T> {
+  pub fn get(&self, suit: Suit) -> T {
+    match suit {
+      Suit::Minor(MinorSuit::Pentacles) => self.pentacles,
+      Suit::Minor(MinorSuit::Cups) => self.cups,
+      Suit::Minor(MinorSuit::Swords) => self.swords,
+      Suit::Minor(MinorSuit::Wands) => self.wands,
+      Suit::MajorArcana => self.major,
+    }
+  }
+}
+
+/// The colours the card art was drawn with.
+const ART_SUITS: PerSuit<BaseColor> = PerSuit {
+  pentacles: BaseColor::Yellow,
+  cups: BaseColor::Red,
+  swords: BaseColor::Cyan,
+  wands: BaseColor::Green,
+  major: BaseColor::Magenta,
+};
+
+const GLYPHS: PerSuit<char> = PerSuit {
+  pentacles: '◆',
+  cups: '♥',
+  swords: '♠',
+  wands: '♣',
+  major: '★',
+};
+
+/// Yellow, blue and white stay apart for most kinds of colour blindness,
+/// and the glyphs tell the rest apart.
+const COLORBLIND_SUITS: PerSuit<BaseColor> = PerSuit {
+  pentacles: BaseColor::Yellow,
+  cups: BaseColor::Red,
+  swords: BaseColor::Blue,
+  wands: BaseColor::White,
+  major: BaseColor::Magenta,
+};
+
+impl Default for Palette {
+  fn default() -> Self {
+    Self::dark()
+  }
+}
+
+impl Palette {
+  fn dark() -> Self {
+    Self {
+      terminal: Terminal::Dark,
+      background: Color::TerminalDefault,
+      border: Color::Light(BaseColor::Black),
+      highlight: Color::Light(BaseColor::White),
+      blocked: Color::Dark(BaseColor::White),
+      target: Color::Light(BaseColor::Blue),
+      suits: ART_SUITS,
+      glyphs: None,
+    }
+  }
+
+  fn light() -> Self {
+    Self {
+      terminal: Terminal::Light,
+      border: Color::Dark(BaseColor::White),
+      highlight: Color::Dark(BaseColor::Black),
+      blocked: Color::Light(BaseColor::Black),
+      target: Color::Dark(BaseColor::Blue),
+      ..Self::dark()
+    }
+  }
+
+  pub fn builtin(name: &str) -> Option<Self> {
+    // Blue's taken by a suit
+    let colorblind = |base: Self| Self {
+      target: match base.terminal {
+        Terminal::Dark => Color::Light(BaseColor::Cyan),
+        Terminal::Light => Color::Dark(BaseColor::Cyan),
+      },
+      suits: COLORBLIND_SUITS,
+      glyphs: Some(GLYPHS),
+      ..base
+    };
+    Some(match name {
+      "dark" => Self::dark(),
+      "light" => Self::light(),
+      "colorblind" => colorblind(Self::dark()),
+      "colorblind-light" => colorblind(Self::light()),
+      _ => return None,
+    })
+  }
+
+  /// Find a theme by name: either a built-in one, or a file in the themes
+  /// folder. A path to a file works too.
+  pub fn load(name: &str) -> Result<Self, String> {
+    if let Some(it) = Self::builtin(name) {
+      return Ok(it);
+    }
+    let path = if name.ends_with(".toml") {
+      Path::new(name).to_path_buf()
+    } else {
+      let Some(dir) = Settings::config_dir() else {
+        return Err(format!("no theme called {:?}", name));
+      };
+      dir.join("themes").join(format!("{}.toml", name))
+    };
+    let text = fs::read_to_string(&path).map_err(|ono| {
+      format!(
+        "no theme called {:?} (built in ones are {}): couldn't read {:?}: {}",
+        name,
+        BUILTIN_THEMES.join(", "),
+        path,
+        ono
+      )
+    })?;
+    let file = toml::from_str::<ThemeFile>(&text)
+      .map_err(|ono| format!("in {:?}: {}", path, ono))?;
+    Ok(file.into_palette())
+  }
+
+  /// The style for a card of this suit: brighter if it's standing out.
+  pub fn suit_style(&self, suit: Suit, emphasis: bool) -> Style {
+    let base = self.suits.get(suit);
+    match (self.terminal, emphasis) {
+      (Terminal::Dark, false) => fg_color(Color::Dark(base)),
+      (Terminal::Dark, true) => fg_color(Color::Light(base)),
+      // light colours wash out on a light background,
+      // so use bold to stand out instead
+      (Terminal::Light, false) => fg_color(Color::Dark(base)),
+      (Terminal::Light, true) => {
+        fg_color(Color::Dark(base)).combine(Effect::Bold)
+      }
+    }
+  }
+
+  pub fn glyph(&self, suit: Suit) -> Option<char> {
+    self.glyphs.map(|glyphs| glyphs.get(suit))
+  }
+
+  /// Turn a colour from the card or board art into the theme's colours.
+  ///
+  /// The suit's colour gets swapped for the theme's, and on light terminals
+  /// the blacks and whites trade places so the art doesn't come out in
+  /// negative.
+  pub fn art_color(&self, color: Color, suit: Option<Suit>) -> Color {
+    let color = match (color, suit) {
+      (Color::Dark(base), Some(suit)) if base == ART_SUITS.get(suit) => {
+        Color::Dark(self.suits.get(suit))
+      }
+      (Color::Light(base), Some(suit)) if base == ART_SUITS.get(suit) => {
+        Color::Light(self.suits.get(suit))
+      }
+      _ => color,
+    };
+    if self.terminal == Terminal::Dark {
+      return color;
+    }
+    match color {
+      Color::Dark(BaseColor::Black) => Color::Light(BaseColor::White),
+      Color::Light(BaseColor::White) => Color::Dark(BaseColor::Black),
+      Color::Light(BaseColor::Black) => Color::Dark(BaseColor::White),
+      Color::Dark(BaseColor::White) => Color::Light(BaseColor::Black),
+      it => it,
+    }
+  }
+}
+
+/// A theme file as written, before it's been filled in with the defaults.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+  terminal: Option<Terminal>,
+  background: Option<ThemeColor>,
+  border: Option<ThemeColor>,
+  highlight: Option<ThemeColor>,
+  blocked: Option<ThemeColor>,
+  target: Option<ThemeColor>,
+  suits: Option<PerSuit<SuitColor>>,
+  glyphs: Option<PerSuit<char>>,
+}
+
+impl ThemeFile {
+  fn into_palette(self) -> Palette {
+    let base = match self.terminal {
+      Some(Terminal::Light) => Palette::light(),
+      _ => Palette::dark(),
+    };
+    Palette {
+      terminal: base.terminal,
+      background: self.background.map_or(base.background, |it| it.0),
+      border: self.border.map_or(base.border, |it| it.0),
+      highlight: self.highlight.map_or(base.highlight, |it| it.0),
+      blocked: self.blocked.map_or(base.blocked, |it| it.0),
+      target: self.target.map_or(base.target, |it| it.0),
+      suits: self.suits.map_or(base.suits, |it| PerSuit {
+        pentacles: it.pentacles.0,
+        cups: it.cups.0,
+        swords: it.swords.0,
+        wands: it.wands.0,
+        major: it.major.0,
+      }),
+      glyphs: self.glyphs.or(base.glyphs),
+    }
+  }
+}
+
+/// Any colour cursive understands, like `red`, `light blue` or `#ff8800`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+struct ThemeColor(Color);
+
+impl TryFrom<String> for ThemeColor {
+  type Error = String;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    Color::parse(&value)
+      .map(ThemeColor)
+      .ok_or_else(|| format!("{:?} isn't a colour", value))
+  }
+}
+
+/// Just the name of a colour, like `red`, without `light`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+struct SuitColor(BaseColor);
+
+impl TryFrom<String> for SuitColor {
+  type Error = String;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    BaseColor::parse(&value).map(SuitColor).ok_or_else(|| {
+      format!(
+        "{:?} isn't a colour; suits can only be one of the 8 basic ones",
+        value
+      )
+    })
   }
 }
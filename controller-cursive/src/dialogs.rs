@@ -0,0 +1,139 @@
+use std::io;
+
+use cursive::{
+  event::{Event, EventResult, Key},
+  view::ViewWrapper,
+  views::Dialog,
+  Cursive, View,
+};
+use teletarot_model::{
+  game::Game,
+  stats::{format_duration, Stats},
+};
+
+use crate::{
+  keymap::{Action, Keymap},
+  menu,
+  settings::Settings,
+  TeletarotView, TELETAROT,
+};
+
+/// A dialog, which the cursor keys from the keymap can get around too.
+struct CursorKeys {
+  dialog: Dialog,
+  keymap: Keymap,
+}
+
+impl ViewWrapper for CursorKeys {
+  cursive::wrap_impl!(self.dialog: Dialog);
+
+  fn wrap_on_event(&mut self, ev: Event) -> EventResult {
+    // Let anything typing into the dialog have first go
+    let result = self.dialog.on_event(ev.clone());
+    if !matches!(result, EventResult::Ignored) {
+      return result;
+    }
+    let key = match self.keymap.action(&ev) {
+      Some(Action::Left) => Key::Left,
+      Some(Action::Right) => Key::Right,
+      Some(Action::Up) => Key::Up,
+      Some(Action::Down) => Key::Down,
+      _ => return result,
+    };
+    self.dialog.on_event(Event::Key(key))
+  }
+}
+
+/// Show a dialog on top of everything.
+pub fn add(siv: &mut Cursive, dialog: Dialog) {
+  let keymap = siv
+    .call_on_name(TELETAROT, |view: &mut TeletarotView| view.keymap.clone())
+    .unwrap_or_default();
+  siv.add_layer(CursorKeys { dialog, keymap });
+}
+
+pub fn show_stats(siv: &mut Cursive) {
+  let Some((generator, seed)) = siv
+    .call_on_name(TELETAROT, |view: &mut TeletarotView| {
+      (view.game.generator(), view.game.seed())
+    })
+  else {
+    return;
+  };
+
+  let stats = match Stats::load_default() {
+    Ok(it) => it,
+    Err(ono) => {
+      add(
+        siv,
+        Dialog::info(format!("Couldn't load your stats: {}", ono)),
+      );
+      return;
+    }
+  };
+  let text = stats.summary(generator, seed);
+  add(siv, Dialog::info(text).title("Stats"));
+}
+
+pub fn show_victory(siv: &mut Cursive, game: &Game, saved: io::Result<()>) {
+  let (title, mut text) = if game.assisted() {
+    let text = format!(
+      "Solved in {} moves and {}, with help from the solver. \
+       It doesn't count as a win.",
+      game.moves(),
+      format_duration(game.elapsed()),
+    );
+    ("Solved", text)
+  } else {
+    let text = format!(
+      "You won in {} moves and {}, scoring {}!",
+      game.moves(),
+      format_duration(game.elapsed()),
+      game.score()
+    );
+    ("Victory", text)
+  };
+  if let Err(ono) = saved {
+    text.push_str(&format!("\n\nCouldn't save your stats: {}", ono));
+  }
+  let generator = game.generator();
+  add(
+    siv,
+    Dialog::text(text)
+      .title(title)
+      .button("New game", move |siv| {
+        siv.pop_layer();
+        menu::new_game(siv, generator, None);
+      })
+      .dismiss_button("Close"),
+  );
+}
+
+/// List what all the keys do.
+pub fn show_help(siv: &mut Cursive) {
+  let keymap = siv
+    .call_on_name(TELETAROT, |view: &mut TeletarotView| view.keymap.clone())
+    .unwrap_or_default();
+
+  let width = Action::ALL
+    .iter()
+    .map(|action| action.description().len())
+    .max()
+    .unwrap_or_default();
+  let mut text = String::new();
+  for action in Action::ALL {
+    text.push_str(&format!(
+      "{:<width$}  {}\n",
+      action.description(),
+      keymap.describe(action),
+    ));
+  }
+  if let Some(dir) = Settings::config_dir() {
+    text.push_str(&format!(
+      "\nChange these in {:?}.",
+      dir.join(Keymap::FILE_NAME)
+    ));
+  }
+
+  add(siv, Dialog::info(text).title("Keys"));
+}
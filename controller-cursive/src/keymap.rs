@@ -0,0 +1,357 @@
+use std::{collections::BTreeMap, fmt, fs, io};
+
+use cursive::event::{Event, Key};
+use serde::Deserialize;
+
+use crate::settings::Settings;
+
+/// Things the player can do with a key.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+  Left,
+  Right,
+  Up,
+  Down,
+  Select,
+  Cancel,
+  Automove,
+  Undo,
+  Hint,
+  Pause,
+  Compact,
+  Autoplay,
+  AutoplayStep,
+  AutoplayFaster,
+  AutoplaySlower,
+  NewGame,
+  Restart,
+  Stats,
+  Menu,
+  Help,
+  Quit,
+}
+
+impl Action {
+  /// In the order they're listed in the help.
+  pub const ALL: [Action; 21] = [
+    Action::Left,
+    Action::Right,
+    Action::Up,
+    Action::Down,
+    Action::Select,
+    Action::Cancel,
+    Action::Automove,
+    Action::Undo,
+    Action::Hint,
+    Action::Pause,
+    Action::Compact,
+    Action::Autoplay,
+    Action::AutoplayStep,
+    Action::AutoplayFaster,
+    Action::AutoplaySlower,
+    Action::NewGame,
+    Action::Restart,
+    Action::Stats,
+    Action::Menu,
+    Action::Help,
+    Action::Quit,
+  ];
+
+  /// What it's called in the keys file.
+  pub fn name(self) -> &'static str {
+    match self {
+      Action::Left => "left",
+      Action::Right => "right",
+      Action::Up => "up",
+      Action::Down => "down",
+      Action::Select => "select",
+      Action::Cancel => "cancel",
+      Action::Automove => "automove",
+      Action::Undo => "undo",
+      Action::Hint => "hint",
+      Action::Pause => "pause",
+      Action::Compact => "compact",
+      Action::Autoplay => "autoplay",
+      Action::AutoplayStep => "autoplay-step",
+      Action::AutoplayFaster => "autoplay-faster",
+      Action::AutoplaySlower => "autoplay-slower",
+      Action::NewGame => "new-game",
+      Action::Restart => "restart",
+      Action::Stats => "stats",
+      Action::Menu => "menu",
+      Action::Help => "help",
+      Action::Quit => "quit",
+    }
+  }
+
+  pub fn description(self) -> &'static str {
+    match self {
+      Action::Left => "Cursor left",
+      Action::Right => "Cursor right",
+      Action::Up => "Cursor up to the foundations",
+      Action::Down => "Cursor down to the columns",
+      Action::Select => "Pick up a card, or put it down",
+      Action::Cancel => "Put the card back",
+      Action::Automove => "Move what can go to the foundations",
+      Action::Undo => "Undo",
+      Action::Hint => "Ask the solver what to do next",
+      Action::Pause => "Pause the clock",
+      Action::Compact => "Switch to small or big cards",
+      Action::Autoplay => "Watch the solver play",
+      Action::AutoplayStep => "While it plays, play one move",
+      Action::AutoplayFaster => "While it plays, play faster",
+      Action::AutoplaySlower => "While it plays, play slower",
+      Action::NewGame => "New game",
+      Action::Restart => "Restart this game",
+      Action::Stats => "Stats",
+      Action::Menu => "Open the menu",
+      Action::Help => "This help",
+      Action::Quit => "Quit",
+    }
+  }
+
+  fn default_keys(self) -> Vec<Binding> {
+    let keys: &[Event] = match self {
+      Action::Left => &[Event::Key(Key::Left), Event::Char('h')],
+      Action::Right => &[Event::Key(Key::Right), Event::Char('l')],
+      Action::Up => &[Event::Key(Key::Up), Event::Char('k')],
+      Action::Down => &[Event::Key(Key::Down), Event::Char('j')],
+      Action::Select => &[Event::Char(' '), Event::Key(Key::Enter)],
+      Action::Cancel => &[Event::Key(Key::Esc)],
+      Action::Automove => &[Event::Char('z')],
+      Action::Undo => &[Event::Char('u')],
+      Action::Hint => &[Event::Char('H')],
+      Action::Pause => &[Event::Char('p')],
+      Action::Compact => &[Event::Char('c')],
+      Action::Autoplay => &[Event::Char('a')],
+      Action::AutoplayStep => &[Event::Char('.')],
+      Action::AutoplayFaster => &[Event::Char('+'), Event::Char('=')],
+      Action::AutoplaySlower => &[Event::Char('-')],
+      Action::NewGame => &[Event::Char('n')],
+      Action::Restart => &[Event::Char('r')],
+      Action::Stats => &[Event::Char('s')],
+      Action::Menu => &[Event::Key(Key::F10)],
+      Action::Help => &[Event::Char('?')],
+      Action::Quit => &[Event::Char('q')],
+    };
+    keys.iter().cloned().map(Binding).collect()
+  }
+}
+
+/// Which keys do what.
+///
+/// Loaded from `keys.toml` in the config directory, where each action gets
+/// a list of keys. Anything left out keeps its usual keys:
+///
+/// ```toml
+/// left = ["Left", "a"]
+/// right = ["Right", "d"]
+/// up = ["Up", "w"]
+/// down = ["Down", "s"]
+/// autoplay = ["ctrl-a"]
+/// stats = ["F2"]
+/// ```
+///
+/// Keys are either a single character, a key name like `Space`, `Esc`,
+/// `PageUp` or `F5`, or one of those after `ctrl-`, `alt-` or `shift-`.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+  bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for Keymap {
+  fn default() -> Self {
+    Self {
+      bindings: Action::ALL
+        .into_iter()
+        .map(|action| (action, action.default_keys()))
+        .collect(),
+    }
+  }
+}
+
+impl Keymap {
+  pub const FILE_NAME: &'static str = "keys.toml";
+
+  /// Load the keys file, or the usual keys if it's not there.
+  ///
+  /// Like the settings, if the file's broken the usual keys come back
+  /// along with the error.
+  pub fn load() -> (Self, Option<String>) {
+    match Self::read_file() {
+      Ok(it) => (it, None),
+      Err(ono) => (Self::default(), Some(ono)),
+    }
+  }
+
+  fn read_file() -> Result<Self, String> {
+    let Some(path) =
+      Settings::config_dir().map(|dir| dir.join(Self::FILE_NAME))
+    else {
+      return Ok(Self::default());
+    };
+    let text = match fs::read_to_string(&path) {
+      Ok(it) => it,
+      Err(ono) if ono.kind() == io::ErrorKind::NotFound => {
+        return Ok(Self::default())
+      }
+      Err(ono) => return Err(format!("couldn't read {:?}: {}", path, ono)),
+    };
+    let file = toml::from_str::<BTreeMap<Action, Vec<Binding>>>(&text)
+      .map_err(|ono| format!("in {:?}: {}", path, ono))?;
+
+    let mut keymap = Self::default();
+    keymap.bindings.extend(file);
+    keymap
+      .check_clashes()
+      .map_err(|ono| format!("in {:?}: {}", path, ono))?;
+    Ok(keymap)
+  }
+
+  /// Make sure no key does two things.
+  fn check_clashes(&self) -> Result<(), String> {
+    let mut seen = Vec::<(&Binding, Action)>::new();
+    for (action, keys) in &self.bindings {
+      for key in keys {
+        if let Some((_, other)) = seen.iter().find(|(it, _)| *it == key) {
+          return Err(format!(
+            "{} is bound to both {} and {}",
+            key,
+            other.name(),
+            action.name()
+          ));
+        }
+        seen.push((key, *action));
+      }
+    }
+    Ok(())
+  }
+
+  /// What the event does, if anything.
+  pub fn action(&self, ev: &Event) -> Option<Action> {
+    self
+      .bindings
+      .iter()
+      .find(|(_, keys)| keys.iter().any(|key| key.0 == *ev))
+      .map(|(action, _)| *action)
+  }
+
+  pub fn events(&self, action: Action) -> impl Iterator<Item = Event> + '_ {
+    self.bindings[&action].iter().map(|key| key.0.clone())
+  }
+
+  /// The keys for the action, written out for people.
+  pub fn describe(&self, action: Action) -> String {
+    let keys = &self.bindings[&action];
+    if keys.is_empty() {
+      return "(none)".to_string();
+    }
+    keys
+      .iter()
+      .map(Binding::to_string)
+      .collect::<Vec<_>>()
+      .join(", ")
+  }
+
+  /// The first key for the action, for showing in the menus.
+  pub fn shortcut(&self, action: Action) -> String {
+    self.bindings[&action]
+      .first()
+      .map(Binding::to_string)
+      .unwrap_or_default()
+  }
+}
+
+/// One key, as written in the keys file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+struct Binding(Event);
+
+/// The keys that have names, by name.
+const KEY_NAMES: [(&str, Key); 26] = [
+  ("Left", Key::Left),
+  ("Right", Key::Right),
+  ("Up", Key::Up),
+  ("Down", Key::Down),
+  ("Enter", Key::Enter),
+  ("Tab", Key::Tab),
+  ("Backspace", Key::Backspace),
+  ("Esc", Key::Esc),
+  ("Insert", Key::Ins),
+  ("Delete", Key::Del),
+  ("Home", Key::Home),
+  ("End", Key::End),
+  ("PageUp", Key::PageUp),
+  ("PageDown", Key::PageDown),
+  ("F1", Key::F1),
+  ("F2", Key::F2),
+  ("F3", Key::F3),
+  ("F4", Key::F4),
+  ("F5", Key::F5),
+  ("F6", Key::F6),
+  ("F7", Key::F7),
+  ("F8", Key::F8),
+  ("F9", Key::F9),
+  ("F10", Key::F10),
+  ("F11", Key::F11),
+  ("F12", Key::F12),
+];
+
+impl TryFrom<String> for Binding {
+  type Error = String;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    let not_a_key = || format!("{:?} isn't a key", value);
+    let (modifier, name) = match value.split_once('-') {
+      Some((modifier, name)) if !name.is_empty() => {
+        (Some(modifier.to_ascii_lowercase()), name)
+      }
+      _ => (None, value.as_str()),
+    };
+
+    let mut chars = name.chars();
+    let ch = chars.next().filter(|_| chars.next().is_none());
+    let key = KEY_NAMES
+      .iter()
+      .find(|(it, _)| it.eq_ignore_ascii_case(name))
+      .map(|(_, key)| *key);
+
+    let event = match (modifier.as_deref(), ch, key) {
+      (None, Some(ch), _) => Event::Char(ch),
+      (None, _, _) if name.eq_ignore_ascii_case("space") => Event::Char(' '),
+      (None, _, Some(key)) => Event::Key(key),
+      (Some("ctrl"), Some(ch), _) => Event::CtrlChar(ch.to_ascii_lowercase()),
+      (Some("alt"), Some(ch), _) => Event::AltChar(ch),
+      (Some("ctrl"), _, Some(key)) => Event::Ctrl(key),
+      (Some("alt"), _, Some(key)) => Event::Alt(key),
+      (Some("shift"), _, Some(key)) => Event::Shift(key),
+      _ => return Err(not_a_key()),
+    };
+    Ok(Binding(event))
+  }
+}
+
+impl fmt::Display for Binding {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let key_name = |key: &Key| {
+      KEY_NAMES
+        .iter()
+        .find(|(_, it)| it == key)
+        .map_or("?", |(name, _)| *name)
+    };
+    match &self.0 {
+      Event::Char(' ') => write!(f, "Space"),
+      Event::Char(ch) => write!(f, "{}", ch),
+      Event::CtrlChar(ch) => write!(f, "ctrl-{}", ch),
+      Event::AltChar(ch) => write!(f, "alt-{}", ch),
+      Event::Key(key) => write!(f, "{}", key_name(key)),
+      Event::Ctrl(key) => write!(f, "ctrl-{}", key_name(key)),
+      Event::Alt(key) => write!(f, "alt-{}", key_name(key)),
+      Event::Shift(key) => write!(f, "shift-{}", key_name(key)),
+      it => write!(f, "{:?}", it),
+    }
+  }
+}
@@ -1,52 +1,294 @@
-use boxes::BoxSide;
+use std::{collections::VecDeque, mem};
+
+use animation::Animation;
+use clap::Parser;
+use colors::Palette;
 use cursive::{
-  event::{Event, EventResult, Key},
-  theme::{BaseColor, Color, ColorType, PaletteColor, Style, Theme},
-  views::{BoxedView, FixedLayout},
-  Cursive, CursiveExt, Printer, Rect, Vec2, View, With,
+  event::{Event, EventResult},
+  theme::{BorderStyle, Color, ColorType, PaletteColor, Style, Theme},
+  view::Nameable,
+  views::Dialog,
+  Printer, Vec2, View, With,
+};
+use keymap::{Action, Keymap};
+use settings::{Args, Layout, Settings};
+use teletarot_model::{
+  game::Game,
+  random::Generator,
+  stats::{format_duration, Stats},
+  Board, BoardZone, Card, CardMoveError, Suit,
 };
-use cursive_hjkl::HjklToDirectionWrapperView;
-use teletarot_model::{Board, BoardZone, Card, Suit};
-use xp::{CardAtlas, CardBorderColor, CARD_HEIGHT, CARD_SIZE, CARD_WIDTH};
+use xp::{CardAtlas, CardBorderColor};
 
+mod animation;
+mod autoplay;
 mod boxes;
 mod colors;
+mod dialogs;
+mod keymap;
+mod menu;
+mod mouse;
+mod settings;
 mod xp;
 
+/// The name the main game view is registered under.
+const TELETAROT: &str = "teletarot";
+/// How many places the cursor can be: the columns, the storage, and the
+/// two foundations.
+const CURSOR_SPOTS: usize = Board::COLUMN_COUNT + 3;
+/// Lines under the board: the status line, then the message line.
+const INFO_ROWS: usize = 2;
+
 fn main() {
+  let args = Args::parse();
+  let (settings, settings_error) = Settings::load(&args);
+  let (keymap, keymap_error) = Keymap::load();
+  let mut errors =
+    Vec::from_iter(settings_error.into_iter().chain(keymap_error));
+  let palette = Palette::load(&settings.theme).unwrap_or_else(|ono| {
+    errors.push(format!("Couldn't load theme: {}", ono));
+    Palette::default()
+  });
+  let ascii = settings.ascii();
+  let atlas = match &settings.art {
+    Some(dir) => CardAtlas::load_pack(dir, palette.clone(), ascii)
+      .unwrap_or_else(|ono| {
+        errors.push(format!("Couldn't load art, using the usual art: {}", ono));
+        CardAtlas::new(palette.clone(), ascii)
+      }),
+    None => CardAtlas::new(palette.clone(), ascii),
+  };
+
   let mut siv = cursive::default();
 
   let theme = Theme::terminal_default().with(|t| {
-    // t.palette[PaletteColor::Background] = Color::Light(BaseColor::White);
+    t.palette[PaletteColor::Background] = palette.background;
+    t.palette[PaletteColor::View] = palette.background;
+    if ascii {
+      // Cursive can only draw its borders with box drawing characters
+      t.borders = BorderStyle::None;
+      t.shadow = false;
+    }
   });
   siv.set_theme(theme);
+  // Keep the clock on the status line ticking. The view speeds it up while
+  // cards are sliding.
+  siv.set_fps(animation::IDLE_FPS);
 
-  let teletarot = TeletarotView::new(None);
-  siv.add_layer(HjklToDirectionWrapperView::new(teletarot));
-  siv.add_global_callback('q', Cursive::quit);
+  let teletarot = TeletarotView::new(
+    Game::new(Generator::Shuffled, None),
+    &settings,
+    atlas,
+    keymap.clone(),
+  );
+  siv.add_layer(teletarot.with_name(TELETAROT));
+  menu::install(&mut siv, &keymap);
+  if !errors.is_empty() {
+    dialogs::add(&mut siv, Dialog::info(errors.join("\n\n")).title("Oops"));
+  }
   siv.run();
 }
 
 struct TeletarotView {
   atlas: CardAtlas,
-  board: Board,
+  /// The atlas not in use right now: the compact one if the full-size one
+  /// is being used, or the other way around.
+  spare_atlas: CardAtlas,
+  layout: Layout,
+  keymap: Keymap,
+  game: Game,
 
   /// If 0..=10, selects the columns.
-  /// If 11, selects the storage over the minor foundation
+  /// If 11, selects the storage over the minor foundation,
+  /// 12 the minor foundation and 13 the major foundation.
   cursor: usize,
   cursor_src: Option<usize>,
+  drag: Option<mouse::Drag>,
+  autoplay: Option<autoplay::Autoplay>,
+  /// Why the last move didn't work, if it didn't.
+  message: Option<String>,
+
+  animate: bool,
+  animations: VecDeque<Animation>,
+  /// Whether the screen's refreshing fast, for animations or autoplay.
+  fast_refresh: bool,
+  /// Once the game's won it's in the stats, and there's nothing left to play.
+  won: bool,
 }
 
 impl TeletarotView {
-  fn new(seed: Option<u64>) -> Self {
+  fn new(
+    game: Game,
+    settings: &Settings,
+    atlas: CardAtlas,
+    keymap: Keymap,
+  ) -> Self {
     Self {
-      atlas: CardAtlas::new(),
-      board: teletarot_model::random::shuffled_random(seed),
+      spare_atlas: CardAtlas::new_compact(
+        atlas.palette().clone(),
+        atlas.ascii(),
+      ),
+      atlas,
+      layout: settings.layout,
+      keymap,
+      game,
       cursor: 0,
       cursor_src: None,
+      drag: None,
+      autoplay: None,
+      message: None,
+      animate: settings.animation,
+      animations: VecDeque::new(),
+      fast_refresh: false,
+      won: false,
+    }
+  }
+
+  /// Throw out the current game and start playing a different one.
+  fn start(&mut self, game: Game) {
+    self.game = game;
+    self.cursor = 0;
+    self.cursor_src = None;
+    self.drag = None;
+    self.autoplay = None;
+    self.message = None;
+    self.won = false;
+    self.skip_animations();
+  }
+
+  fn board(&self) -> &Board {
+    self.game.board()
+  }
+
+  /// If the game was left half-played, count it as abandoned.
+  fn abandon(&mut self) {
+    if self.game.in_progress() {
+      // Nowhere good to show an error when quitting
+      let _ = Stats::record_to_default_path(&self.game);
+    }
+  }
+
+  /// Move a card as the player, dropping whatever was selected.
+  ///
+  /// Cards dragged with the mouse are already where they're going,
+  /// so those moves shouldn't be animated.
+  fn try_move(
+    &mut self,
+    src: BoardZone,
+    dst: BoardZone,
+    animate: bool,
+  ) -> EventResult {
+    self.cursor_src = None;
+    self.skip_animations();
+
+    let before = self.board().clone();
+    let result = self.game.move_card(src, dst);
+    let moved = result.is_ok();
+    self.message = result.err().map(|ono| ono.to_string());
+    if moved && animate {
+      self.queue_animation(&before);
+    }
+    if moved && self.game.is_solved() {
+      return self.win();
+    }
+    EventResult::consumed()
+  }
+
+  /// Switch to the other layout for good, whether or not it fits.
+  pub fn toggle_compact(&mut self) {
+    self.layout = if self.atlas.compact() {
+      Layout::Full
+    } else {
+      Layout::Compact
+    };
+  }
+
+  /// Switch between the full-size and compact atlases.
+  fn use_compact_atlas(&mut self, compact: bool) {
+    if self.atlas.compact() != compact {
+      mem::swap(&mut self.atlas, &mut self.spare_atlas);
+      // Anything mid-flight was going between the old atlas's positions
+      self.skip_animations();
+      self.drag = None;
+    }
+  }
+
+  fn cursor_zone(&self) -> BoardZone {
+    idx_to_board_zone(self.cursor)
+  }
+
+  fn src_zone(&self) -> Option<BoardZone> {
+    self.cursor_src.map(idx_to_board_zone)
+  }
+
+  /// The card on top of whatever's selected.
+  fn moving_card(&self) -> Option<&Card> {
+    match self.src_zone()? {
+      BoardZone::Column(idx) => self.board().get_column(idx).last(),
+      _ => self.board().minor_foundation_storage(),
+    }
+  }
+
+  /// Everywhere whatever's selected could go.
+  fn targets(&self) -> Vec<BoardZone> {
+    // The board on screen isn't the real one until the cards settle
+    if !self.animations.is_empty() {
+      return Vec::new();
+    }
+    self
+      .src_zone()
+      .map(|src| self.board().legal_destinations(src))
+      .unwrap_or_default()
+  }
+
+  /// Move the cursor to the zone closest to it in the other row:
+  /// the columns, or the foundations and storage above them.
+  fn cursor_to_other_row(&mut self, up: bool) {
+    let x_of = |zone| match zone {
+      BoardZone::Column(idx) => self.atlas.column_poses()[idx].x,
+      BoardZone::MinorFoundationStorage => self.atlas.min_fndn_storage().x,
+      BoardZone::MinorFoundation => self.atlas.min_fndn_poses()[0].x,
+      BoardZone::MajorFoundation => self.atlas.maj_fndn_left().x,
+    };
+    let here = x_of(self.cursor_zone());
+    let closest = BoardZone::all()
+      .filter(|zone| matches!(zone, BoardZone::Column(_)) != up)
+      .min_by_key(|zone| x_of(*zone).abs_diff(here));
+    if let Some(zone) = closest {
+      self.cursor = board_zone_to_idx(zone);
+    }
+  }
+
+  /// Move every card that can go to the foundations, one at a time.
+  fn automove(&mut self) {
+    self.skip_animations();
+    self.queue_automoves();
+  }
+
+  /// Like [`TeletarotView::automove`], but after whatever's animating now.
+  fn queue_automoves(&mut self) {
+    loop {
+      let before = self.board().clone();
+      if self.game.next_automove().is_none() {
+        break;
+      }
+      self.queue_animation(&before);
     }
   }
 
+  /// Put the won game in the record books and tell the player about it.
+  fn win(&mut self) -> EventResult {
+    if self.won {
+      return EventResult::consumed();
+    }
+    self.won = true;
+    let saved = Stats::record_to_default_path(&self.game);
+    let game = self.game.clone();
+    EventResult::with_cb_once(move |siv| {
+      dialogs::show_victory(siv, &game, saved);
+    })
+  }
+
   fn draw_foundations(&self, printer: &Printer) {
     // There will be 22 cards here.
     // Each of them will be tightly packed, but the last card
@@ -58,101 +300,126 @@ impl TeletarotView {
     |||   |||
     */
 
-    let final_major = match (
-      self.board.major_foundation_left_max(),
-      self.board.major_foundation_right_min(),
-    ) {
-      (Some(left), Some(right)) if left + 1 == right => Some(left),
-      _ => None,
-    };
+    let board = self.shown_board();
+    let final_major = animation::final_major(board);
+    let targets = self.targets();
+    let target_style = fg_color(self.atlas.palette().target);
+    let target = CardBorderColor::Custom(target_style);
+    let border = fg_color(self.atlas.palette().border);
+    let moving_card = self.moving_card();
+
+    let major_cursor = self.cursor_zone() == BoardZone::MajorFoundation;
+    let major_target = targets.contains(&BoardZone::MajorFoundation);
+    // Which end of the major foundation the selected card would go on
+    let goes_left =
+      moving_card.is_some_and(|card| match board.major_foundation_left_max() {
+        Some(max) => card.number() == max + 1,
+        None => card.number() == Card::MAJOR_ARCANA_MIN,
+      });
 
     // Print right first so that the leftmost card appears on top
-    for (idx, card) in self
-      .board
-      .virtual_cards_major_foundation_right()
-      .iter()
-      .enumerate()
-    {
-      self.atlas.print_card(
-        card,
-        self.atlas.maj_fndn_right() - (CARD_WIDTH + idx + 1, 0),
-        printer,
-        false,
-        CardBorderColor::HilightIfThickBorder,
-      );
-    }
-    for (idx, card) in self
-      .board
-      .virtual_cards_major_foundation_left()
-      .iter()
-      .enumerate()
-    {
-      let is_final = Some(idx as u8) == final_major;
-      self.atlas.print_card(
-        card,
-        self.atlas.maj_fndn_left()
-          + (idx + if is_final { CARD_WIDTH / 2 } else { 0 }, 0)
-          - (0, if is_final { 1 } else { 0 }),
-        printer,
-        false,
-        CardBorderColor::HilightIfThickBorder,
-      );
+    for (left, cards) in [
+      (false, board.virtual_cards_major_foundation_right()),
+      (true, board.virtual_cards_major_foundation_left()),
+    ] {
+      let is_target = major_target && goes_left == left;
+      if cards.is_empty() && (major_cursor || is_target) {
+        let pos = if left {
+          self.atlas.major_left_pos(0, false)
+        } else {
+          self.atlas.major_right_pos(0)
+        };
+        let style = if is_target { target_style } else { border };
+        self.atlas.print_slot(printer, pos, style, major_cursor);
+      }
+
+      for (idx, card) in cards.iter().enumerate() {
+        if self.is_flying(card) {
+          continue;
+        }
+        let pos = if left {
+          let is_final = Some(idx as u8) == final_major;
+          self.atlas.major_left_pos(idx, is_final)
+        } else {
+          self.atlas.major_right_pos(idx)
+        };
+        let is_end = idx + 1 == cards.len();
+        let cbc = if is_end && is_target {
+          target
+        } else {
+          CardBorderColor::HilightIfThickBorder
+        };
+        self
+          .atlas
+          .print_card(card, pos, printer, major_cursor && is_end, cbc);
+      }
     }
 
     // Minor foundation
-    let minor_blocked = self.board.minor_foundation_storage().is_some();
-    for (suit_idx, stack) in self
-      .board
-      .virtual_cards_minor_foundation()
-      .iter()
-      .enumerate()
+    let minor_blocked = board.minor_foundation_storage().is_some();
+    let minor_cursor = self.cursor_zone() == BoardZone::MinorFoundation;
+    let minor_target = moving_card
+      .filter(|_| targets.contains(&BoardZone::MinorFoundation))
+      .and_then(|card| match card.suit() {
+        Suit::Minor(suit) => Some(suit as usize),
+        Suit::MajorArcana => None,
+      });
+    for (suit_idx, stack) in
+      board.virtual_cards_minor_foundation().iter().enumerate()
     {
       let pos = self.atlas.min_fndn_poses()[suit_idx];
+      let is_target = minor_target == Some(suit_idx);
       // slot, just in case
-      BoxSide::draw_box(
-        printer,
-        pos,
-        (CARD_WIDTH + 2, CARD_HEIGHT + 2),
-        fg_color(Color::Light(BaseColor::Black)),
-        false,
-      );
+      let slot_style = if is_target { target_style } else { border };
+      self
+        .atlas
+        .print_slot(printer, pos, slot_style, minor_cursor);
 
-      let Some(card) = stack.last() else { continue };
-      let cbc = if minor_blocked {
-        CardBorderColor::Custom(fg_color(Color::Dark(BaseColor::White)))
+      let Some(card) = stack.iter().rev().find(|c| !self.is_flying(c)) else {
+        continue;
+      };
+      let cbc = if is_target {
+        target
+      } else if minor_blocked {
+        CardBorderColor::Custom(fg_color(self.atlas.palette().blocked))
       } else {
         CardBorderColor::HilightIfThickBorder
       };
-      self.atlas.print_card(card, pos, printer, false, cbc);
+      self.atlas.print_card(card, pos, printer, minor_cursor, cbc);
     }
 
     // Minor storage
-    let normal_column = self.cursor == 11;
-    let src_column = self.cursor_src == Some(11);
+    let normal_column = self.cursor_zone() == BoardZone::MinorFoundationStorage;
+    let src_column = self.src_zone() == Some(BoardZone::MinorFoundationStorage);
     let selected = normal_column || src_column;
+    let is_target = targets.contains(&BoardZone::MinorFoundationStorage);
 
-    if selected {
-      BoxSide::draw_box(
+    if selected || is_target {
+      let style = if is_target {
+        target_style
+      } else {
+        fg_color(self.atlas.palette().blocked)
+      };
+      self
+        .atlas
+        .print_blank(printer, self.atlas.min_fndn_storage());
+      self.atlas.print_slot(
         printer,
         self.atlas.min_fndn_storage(),
-        CARD_SIZE + Vec2::new(2, 2),
-        fg_color(Color::Dark(BaseColor::White)),
+        style,
         selected,
       );
-      printer.with_color(Color::Light(BaseColor::Black).into(), |prn| {
-        prn.print_rect(
-          Rect::from_size(
-            Vec2::from(self.atlas.min_fndn_storage()) + Vec2::new(1, 1),
-            CARD_SIZE,
-          ),
-          " ",
-        );
-      });
     }
 
-    if let Some(storage) = self.board.minor_foundation_storage() {
+    let storage_carried = self.drag.as_ref().is_some_and(|drag| {
+      drag.is_carrying(BoardZone::MinorFoundationStorage, 0)
+    });
+    let storage = board
+      .minor_foundation_storage()
+      .filter(|card| !storage_carried && !self.is_flying(card));
+    if let Some(storage) = storage {
       let cbc = if src_column {
-        CardBorderColor::Custom(fg_color(Color::Light(BaseColor::White)))
+        CardBorderColor::Custom(fg_color(self.atlas.palette().highlight))
       } else {
         CardBorderColor::HilightIfThickBorder
       };
@@ -167,47 +434,96 @@ impl TeletarotView {
     }
   }
 
+  /// Draw whatever's being dragged around with the mouse, on top of
+  /// everything else.
+  fn draw_drag(&self, printer: &Printer) {
+    let Some(corner) = self.drag.as_ref().and_then(mouse::Drag::corner) else {
+      return;
+    };
+    for (idx, card) in self.dragged_cards().iter().enumerate() {
+      self.atlas.print_card(
+        card,
+        corner + (0, idx * self.atlas.fan_step()),
+        printer,
+        true,
+        CardBorderColor::Custom(fg_color(self.atlas.palette().highlight)),
+      );
+    }
+  }
+
+  fn status_text(&self) -> String {
+    let mut status = format!(
+      "{} #{}  Moves {}  Undos {}  Time {}  Score {}",
+      self.game.generator(),
+      self.game.seed(),
+      self.game.moves(),
+      self.game.undos(),
+      format_duration(self.game.elapsed()),
+      self.game.score()
+    );
+    if self.game.is_paused() {
+      status.push_str("  (paused)");
+    }
+    status
+  }
+
+  /// What goes on the line under the status line.
+  fn message_text(&self) -> Option<String> {
+    match &self.autoplay {
+      Some(auto) => Some(auto.status_text()),
+      None => self.message.clone(),
+    }
+  }
+
+  fn draw_status(&self, printer: &Printer) {
+    let y = self.atlas.board_size().y;
+    printer.print((0, y), &self.status_text());
+    if let Some(message) = self.message_text() {
+      printer.with_style(fg_color(self.atlas.palette().highlight), |prn| {
+        prn.print((0, y + 1), &message);
+      });
+    }
+  }
+
   fn draw_tableau(&self, printer: &Printer) {
-    for (col_idx, col) in self.board.columns().iter().enumerate() {
+    let board = self.shown_board();
+    let targets = self.targets();
+    for (col_idx, col) in board.columns().iter().enumerate() {
       let base_pos = self.atlas.column_poses()[col_idx];
+      let is_target = targets.contains(&BoardZone::Column(col_idx));
       // base slot
-      BoxSide::draw_box(
+      let slot_color = if is_target {
+        self.atlas.palette().target
+      } else {
+        self.atlas.palette().border
+      };
+      self.atlas.print_slot(
         printer,
         base_pos,
-        (CARD_WIDTH + 2, CARD_HEIGHT + 2),
-        fg_color(Color::Light(BaseColor::Black)),
+        fg_color(slot_color),
         col_idx == self.cursor,
       );
 
       for (card_idx, card) in col.iter().enumerate() {
-        let pos = base_pos + (0, card_idx * 2);
+        let carried = self.drag.as_ref().is_some_and(|drag| {
+          drag.is_carrying(BoardZone::Column(col_idx), card_idx)
+        });
+        if carried || self.is_flying(card) {
+          continue;
+        }
+        let pos = self.atlas.card_pos(col_idx, col, card_idx);
 
         let normal_column = col_idx == self.cursor;
         let src_column = Some(col_idx) == self.cursor_src;
         let selected =
           card_idx == col.len() - 1 && (normal_column || src_column);
 
-        let is_next_card = match card.suit() {
-          Suit::Minor(suit) => {
-            let foundation_max =
-              self.board.minor_foundation_maxes()[suit as usize];
-            card.number() == foundation_max.unwrap_or_default() + 1
-          }
-          Suit::MajorArcana => {
-            let next_hi_min = match self.board.major_foundation_left_max() {
-              Some(it) => card.number() == it + 1,
-              None => card.number() == 0,
-            };
-            let next_lo_max = match self.board.major_foundation_right_min() {
-              Some(it) => card.number() + 1 == it,
-              None => card.number() == Card::MAJOR_ARCANA_MAX,
-            };
-            next_hi_min || next_lo_max
-          }
-        };
+        let is_next_card = board.is_next_for_foundation(card);
 
         let cbc = if selected && src_column {
-          CardBorderColor::Custom(fg_color(Color::Light(BaseColor::White)))
+          CardBorderColor::Custom(fg_color(self.atlas.palette().highlight))
+        } else if is_target && card_idx == col.len() - 1 {
+          CardBorderColor::Custom(fg_color(self.atlas.palette().target))
         } else if is_next_card {
           CardBorderColor::AlwaysHilight
         } else {
@@ -218,49 +534,165 @@ impl TeletarotView {
       }
     }
   }
+
+  /// Do whatever the player pressed a key for.
+  fn on_action(&mut self, action: Action) -> EventResult {
+    let plays = matches!(
+      action,
+      Action::Select
+        | Action::Undo
+        | Action::Autoplay
+        | Action::Hint
+        | Action::Automove
+    );
+    if plays && self.won {
+      return EventResult::consumed();
+    }
+    match action {
+      Action::Left => {
+        self.cursor = (self.cursor + CURSOR_SPOTS - 1) % CURSOR_SPOTS;
+      }
+      Action::Right => {
+        self.cursor = (self.cursor + 1) % CURSOR_SPOTS;
+      }
+      Action::Up => self.cursor_to_other_row(true),
+      Action::Down => self.cursor_to_other_row(false),
+      Action::Select => {
+        let zone = self.cursor_zone();
+        if let Some(src_zone) = self.src_zone() {
+          return self.try_move(src_zone, zone, true);
+        } else if zone.is_write_only() {
+          let ono = CardMoveError::WriteOnlySource { zone };
+          self.message = Some(ono.to_string());
+        } else {
+          self.cursor_src = Some(self.cursor);
+        }
+      }
+      Action::Cancel => {
+        self.cursor_src = None;
+        self.message = None;
+      }
+      Action::Undo => {
+        self.skip_animations();
+        let before = self.board().clone();
+        if self.game.undo() {
+          self.queue_animation(&before);
+        }
+        self.cursor_src = None;
+        self.drag = None;
+      }
+      Action::Pause => {
+        if self.game.is_paused() {
+          self.game.resume();
+        } else {
+          self.game.pause();
+        }
+      }
+      Action::Compact => self.toggle_compact(),
+      Action::Autoplay => self.start_autoplay(),
+      Action::Hint => self.start_hint(),
+      Action::Automove => {
+        self.automove();
+        if self.game.is_solved() {
+          return self.win();
+        }
+      }
+      // These only do anything while the solver's playing
+      Action::AutoplayStep
+      | Action::AutoplayFaster
+      | Action::AutoplaySlower => return EventResult::Ignored,
+      // These work from anywhere, so they're global callbacks
+      Action::NewGame
+      | Action::Restart
+      | Action::Stats
+      | Action::Menu
+      | Action::Help
+      | Action::Quit => return EventResult::Ignored,
+    }
+    EventResult::consumed()
+  }
 }
 
 impl View for TeletarotView {
-  fn required_size(&mut self, _constraint: cursive::Vec2) -> cursive::Vec2 {
-    self.atlas.board_size()
+  fn required_size(&mut self, constraint: cursive::Vec2) -> cursive::Vec2 {
+    let compact = match self.layout {
+      Layout::Full => false,
+      Layout::Compact => true,
+      Layout::Auto => {
+        let full = if self.atlas.compact() {
+          &self.spare_atlas
+        } else {
+          &self.atlas
+        };
+        !(full.board_size() + (0, INFO_ROWS)).fits_in(constraint)
+      }
+    };
+    self.use_compact_atlas(compact);
+
+    // Plus the status and message lines, which can be wider than a compact
+    // board
+    let board_size = self.atlas.board_size();
+    let message_width = self.message_text().map_or(0, |it| it.chars().count());
+    Vec2::new(
+      board_size
+        .x
+        .max(self.status_text().chars().count())
+        .max(message_width),
+      board_size.y + INFO_ROWS,
+    )
   }
 
   fn draw(&self, printer: &cursive::Printer) {
     self.atlas.print_background(printer);
     self.draw_foundations(printer);
     self.draw_tableau(printer);
+    self.draw_flights(printer);
+    self.draw_drag(printer);
+    self.draw_status(printer);
   }
 
   fn on_event(&mut self, ev: Event) -> EventResult {
-    match ev {
-      Event::Key(Key::Left) => {
-        self.cursor = (self.cursor + 12 - 1) % 12;
-        EventResult::consumed()
-      }
-      Event::Key(Key::Right) => {
-        self.cursor = (self.cursor + 1) % 12;
-        EventResult::consumed()
-      }
-      Event::Char(' ') | Event::Key(Key::Enter) => {
-        if let Some(src) = self.cursor_src {
-          let src_zone = idx_to_board_zone(src);
-          let dst_zone = idx_to_board_zone(self.cursor);
-          let _ = self.board.move_card(src_zone, dst_zone, true);
+    let result = self.on_board_event(ev);
+    // Only refresh fast while there's something moving on its own
+    let busy = !self.animations.is_empty() || self.autoplay.is_some();
+    if busy == self.fast_refresh {
+      return result;
+    }
+    self.fast_refresh = busy;
+    let fps = if busy {
+      animation::ANIMATION_FPS
+    } else {
+      animation::IDLE_FPS
+    };
+    result.and(EventResult::with_cb(move |siv| siv.set_fps(fps)))
+  }
+}
 
-          self.cursor_src = None;
-        } else {
-          self.cursor_src = Some(self.cursor);
-        }
-        EventResult::consumed()
-      }
-      Event::Key(Key::Esc) => {
-        self.cursor_src = None;
-        EventResult::consumed()
+impl TeletarotView {
+  fn on_board_event(&mut self, ev: Event) -> EventResult {
+    if self.autoplay.is_some() {
+      if ev == Event::Refresh {
+        self.advance_animation();
       }
-      Event::Char('z') => {
-        self.board.check_automove_cards();
-        EventResult::consumed()
+      return self.on_autoplay_event(ev);
+    }
+
+    if let Some(action) = self.keymap.action(&ev) {
+      return self.on_action(action);
+    }
+    match ev {
+      Event::Refresh => {
+        self.advance_animation();
+        EventResult::Ignored
       }
+      Event::Mouse {
+        offset,
+        position,
+        event,
+      } => match position.checked_sub(offset) {
+        Some(pos) => self.on_mouse(pos, event),
+        None => EventResult::Ignored,
+      },
       _ => EventResult::Ignored,
     }
   }
@@ -273,9 +705,19 @@ fn fg_color(color: Color) -> Style {
 }
 
 fn idx_to_board_zone(idx: usize) -> BoardZone {
-  if idx < Board::COLUMN_COUNT {
-    BoardZone::Column(idx)
-  } else {
-    BoardZone::MinorFoundationStorage
+  match idx.checked_sub(Board::COLUMN_COUNT) {
+    None => BoardZone::Column(idx),
+    Some(0) => BoardZone::MinorFoundationStorage,
+    Some(1) => BoardZone::MinorFoundation,
+    _ => BoardZone::MajorFoundation,
+  }
+}
+
+fn board_zone_to_idx(zone: BoardZone) -> usize {
+  match zone {
+    BoardZone::Column(idx) => idx,
+    BoardZone::MinorFoundationStorage => Board::COLUMN_COUNT,
+    BoardZone::MinorFoundation => Board::COLUMN_COUNT + 1,
+    BoardZone::MajorFoundation => Board::COLUMN_COUNT + 2,
   }
 }
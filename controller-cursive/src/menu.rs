@@ -0,0 +1,203 @@
+use cursive::{
+  menu::Tree,
+  traits::Nameable,
+  views::{Dialog, EditView},
+  Cursive,
+};
+use teletarot_model::{game::Game, random::Generator};
+
+use crate::{
+  dialogs,
+  keymap::{Action, Keymap},
+  TeletarotView, TELETAROT,
+};
+
+/// The name of the seed entry box in the new game dialog.
+const SEED_EDIT: &str = "seed";
+
+/// Set up the menubar and the keys that go with it.
+pub fn install(siv: &mut Cursive, keymap: &Keymap) {
+  rebuild_menubar(siv);
+  siv.set_autohide_menu(false);
+
+  for action in Action::ALL {
+    let callback: fn(&mut Cursive) = match action {
+      Action::NewGame => |siv| {
+        let generator = current_generator(siv);
+        new_game(siv, generator, None);
+      },
+      Action::Restart => restart,
+      Action::Stats => dialogs::show_stats,
+      Action::Menu => Cursive::select_menubar,
+      Action::Help => dialogs::show_help,
+      Action::Quit => quit,
+      // The rest are up to the game view
+      _ => continue,
+    };
+    for ev in keymap.events(action) {
+      siv.add_global_callback(ev, callback);
+    }
+  }
+}
+
+/// (Re)make the menubar, so the generator menu shows the one in use.
+fn rebuild_menubar(siv: &mut Cursive) {
+  let current = current_generator(siv);
+  let keymap = siv
+    .call_on_name(TELETAROT, |view: &mut TeletarotView| view.keymap.clone())
+    .unwrap_or_default();
+  // Line the keys up after the names
+  let label =
+    |name: &str, action| format!("{:<11}{}", name, keymap.shortcut(action));
+
+  let mut generators = Tree::new();
+  for generator in Generator::ALL {
+    let mark = if generator == current { "(*)" } else { "( )" };
+    generators.add_leaf(format!("{} {}", mark, generator), move |siv| {
+      new_game(siv, generator, None);
+    });
+  }
+
+  let menubar = siv.menubar();
+  menubar.clear();
+  menubar.add_subtree(
+    "Game",
+    Tree::new()
+      .leaf(label("New game", Action::NewGame), |siv| {
+        let generator = current_generator(siv);
+        new_game(siv, generator, None);
+      })
+      .leaf("New game from seed...", ask_for_seed)
+      .leaf(label("Restart", Action::Restart), restart)
+      .subtree("Generator", generators)
+      .delimiter()
+      .leaf(label("Hint", Action::Hint), |siv| {
+        siv.call_on_name(TELETAROT, |view: &mut TeletarotView| {
+          if view.autoplay.is_none() {
+            view.start_hint();
+          }
+        });
+      })
+      .leaf(label("Autoplay", Action::Autoplay), |siv| {
+        siv.call_on_name(TELETAROT, |view: &mut TeletarotView| {
+          if view.autoplay.is_none() {
+            view.start_autoplay();
+          }
+        });
+      })
+      .leaf(label("Compact", Action::Compact), |siv| {
+        siv.call_on_name(TELETAROT, TeletarotView::toggle_compact);
+      })
+      .leaf(label("Stats", Action::Stats), dialogs::show_stats)
+      .leaf(label("Keys", Action::Help), dialogs::show_help)
+      .delimiter()
+      .leaf(label("Quit", Action::Quit), quit),
+  );
+}
+
+fn current_generator(siv: &mut Cursive) -> Generator {
+  siv
+    .call_on_name(TELETAROT, |view: &mut TeletarotView| view.game.generator())
+    .unwrap_or(Generator::Shuffled)
+}
+
+fn in_progress(siv: &mut Cursive) -> bool {
+  siv
+    .call_on_name(TELETAROT, |view: &mut TeletarotView| {
+      view.game.in_progress()
+    })
+    .unwrap_or(false)
+}
+
+/// Throw away the current game and deal another,
+/// checking first if that'd abandon anything.
+pub fn new_game(siv: &mut Cursive, generator: Generator, seed: Option<u64>) {
+  confirm_abandon(siv, "Deal a new game?", move |siv| {
+    siv.call_on_name(TELETAROT, |view: &mut TeletarotView| {
+      view.abandon();
+      view.start(Game::new(generator, seed));
+    });
+    rebuild_menubar(siv);
+  });
+}
+
+/// Deal the same game again.
+fn restart(siv: &mut Cursive) {
+  let Some((generator, seed)) = siv
+    .call_on_name(TELETAROT, |view: &mut TeletarotView| {
+      (view.game.generator(), view.game.seed())
+    })
+  else {
+    return;
+  };
+  new_game(siv, generator, Some(seed));
+}
+
+fn quit(siv: &mut Cursive) {
+  confirm_abandon(siv, "Quit?", |siv| {
+    siv.call_on_name(TELETAROT, |view: &mut TeletarotView| {
+      view.abandon();
+    });
+    siv.quit();
+  });
+}
+
+/// Do something, but if there's a game going ask the player first.
+fn confirm_abandon<F>(siv: &mut Cursive, question: &str, then: F)
+where
+  F: Fn(&mut Cursive) + Send + Sync + 'static,
+{
+  if !in_progress(siv) {
+    then(siv);
+    return;
+  }
+  dialogs::add(
+    siv,
+    Dialog::text(format!(
+      "{}\nThe game in progress will count as abandoned.",
+      question
+    ))
+    .title("Abandon game?")
+    .button("Yes", move |siv| {
+      siv.pop_layer();
+      then(siv);
+    })
+    .dismiss_button("No"),
+  );
+}
+
+fn ask_for_seed(siv: &mut Cursive) {
+  let deal = |siv: &mut Cursive| {
+    let text = siv
+      .call_on_name(SEED_EDIT, |edit: &mut EditView| edit.get_content())
+      .unwrap_or_default();
+    match text.trim().parse::<u64>() {
+      Ok(seed) => {
+        siv.pop_layer();
+        let generator = current_generator(siv);
+        new_game(siv, generator, Some(seed));
+      }
+      Err(_) => {
+        dialogs::add(
+          siv,
+          Dialog::info(format!(
+            "{:?} isn't a seed; it should be a whole number.",
+            text.as_str()
+          )),
+        );
+      }
+    }
+  };
+
+  dialogs::add(
+    siv,
+    Dialog::around(
+      EditView::new()
+        .on_submit(move |siv, _| deal(siv))
+        .with_name(SEED_EDIT),
+    )
+    .title("Seed to deal")
+    .button("Deal", deal)
+    .dismiss_button("Cancel"),
+  );
+}
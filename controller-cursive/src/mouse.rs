@@ -0,0 +1,201 @@
+use cursive::{
+  event::{EventResult, MouseButton, MouseEvent},
+  Rect, Vec2,
+};
+use teletarot_model::{BoardZone, Card};
+
+use crate::{board_zone_to_idx, idx_to_board_zone, TeletarotView};
+
+/// What's under a cell of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+  pub zone: BoardZone,
+  /// Which card in the zone was hit. For columns, this is the index in the
+  /// column; for the storage, it's always 0.
+  /// `None` if the zone was hit but there's no card there.
+  pub depth: Option<usize>,
+}
+
+/// A card, or run of cards, picked up with the mouse.
+#[derive(Debug, Clone)]
+pub struct Drag {
+  pub src: BoardZone,
+  /// The first card being carried, as a depth like in [`Hit`].
+  pub depth: usize,
+  /// Where the mouse grabbed the first card, from its corner.
+  pub grab_offset: Vec2,
+  /// Where the mouse is now, once it's moved from where it was pressed.
+  pub pos: Option<Vec2>,
+  /// Whether the zone was already selected when it got clicked,
+  /// so clicking it again deselects it.
+  was_selected: bool,
+}
+
+impl Drag {
+  /// Where to draw the first carried card, if it's been picked up.
+  pub fn corner(&self) -> Option<Vec2> {
+    self.pos.map(|pos| pos.saturating_sub(self.grab_offset))
+  }
+
+  /// Whether the given card is being carried away from its zone.
+  pub fn is_carrying(&self, zone: BoardZone, depth: usize) -> bool {
+    self.pos.is_some() && self.src == zone && depth >= self.depth
+  }
+}
+
+impl TeletarotView {
+  /// Figure out what zone and card are at the given position in the view.
+  pub fn hit_test(&self, pos: Vec2) -> Option<Hit> {
+    let card_box = self.atlas.card_box();
+
+    for (col_idx, col) in self.board().columns().iter().enumerate() {
+      let base_pos = self.atlas.column_poses()[col_idx];
+      let offsets = self.atlas.column_offsets(col_idx, col);
+      let height = card_box.y + offsets.last().copied().unwrap_or_default();
+      if Rect::from_size(base_pos, (card_box.x, height)).contains(pos) {
+        // The card on top of the pile at that row
+        let depth = offsets
+          .iter()
+          .rposition(|offset| base_pos.y + offset <= pos.y);
+        return Some(Hit {
+          zone: BoardZone::Column(col_idx),
+          depth,
+        });
+      }
+    }
+
+    // The storage is drawn on top of the minor foundation, so check it first
+    let storage = self.atlas.min_fndn_storage();
+    if Rect::from_size(storage, card_box).contains(pos) {
+      return Some(Hit {
+        zone: BoardZone::MinorFoundationStorage,
+        depth: self.board().minor_foundation_storage().map(|_| 0),
+      });
+    }
+
+    if self
+      .atlas
+      .min_fndn_poses()
+      .iter()
+      .any(|slot| Rect::from_size(*slot, card_box).contains(pos))
+    {
+      return Some(Hit {
+        zone: BoardZone::MinorFoundation,
+        depth: None,
+      });
+    }
+
+    if self.atlas.major_rect().contains(pos) {
+      return Some(Hit {
+        zone: BoardZone::MajorFoundation,
+        depth: None,
+      });
+    }
+
+    None
+  }
+
+  pub fn on_mouse(&mut self, pos: Vec2, event: MouseEvent) -> EventResult {
+    if self.won {
+      return EventResult::Ignored;
+    }
+    match event {
+      MouseEvent::Press(MouseButton::Left) => self.on_mouse_press(pos),
+      MouseEvent::Hold(MouseButton::Left) => {
+        let Some(drag) = &mut self.drag else {
+          return EventResult::Ignored;
+        };
+        drag.pos = Some(pos);
+        EventResult::consumed()
+      }
+      MouseEvent::Release(MouseButton::Left) => {
+        let Some(drag) = self.drag.take() else {
+          return EventResult::Ignored;
+        };
+        if let Some(pos) = drag.pos {
+          match self.hit_test(pos) {
+            Some(hit) if hit.zone != drag.src => {
+              return self.try_move(drag.src, hit.zone, false);
+            }
+            // Dropped back where it came from, or on the table
+            _ => self.cursor_src = None,
+          }
+        } else if drag.was_selected {
+          self.cursor_src = None;
+        }
+        EventResult::consumed()
+      }
+      _ => EventResult::Ignored,
+    }
+  }
+
+  fn on_mouse_press(&mut self, pos: Vec2) -> EventResult {
+    let hit = self.hit_test(pos);
+
+    // If something's already picked up, clicking elsewhere puts it there
+    let selected = self.cursor_src.map(idx_to_board_zone);
+    if let (Some(src), Some(hit)) = (selected, hit) {
+      if hit.zone != src {
+        return self.try_move(src, hit.zone, true);
+      }
+    }
+
+    let Some(hit) = hit else {
+      self.cursor_src = None;
+      return EventResult::consumed();
+    };
+    let idx = board_zone_to_idx(hit.zone);
+    self.cursor = idx;
+
+    // Only the run on top of a column can be picked up, and it all comes
+    // along no matter which card of it was grabbed.
+    let carried = match hit.zone {
+      BoardZone::Column(col_idx) => {
+        let col = self.board().get_column(col_idx);
+        let run_start = col.len() - col.movable_run_len();
+        hit.depth.filter(|d| *d >= run_start).map(|_| run_start)
+      }
+      _ => hit.depth,
+    };
+    let Some(depth) = carried else {
+      self.cursor_src = None;
+      return EventResult::consumed();
+    };
+
+    let corner = match hit.zone {
+      BoardZone::Column(col_idx) => {
+        let col = self.board().get_column(col_idx);
+        self.atlas.card_pos(col_idx, col, depth)
+      }
+      _ => self.atlas.min_fndn_storage(),
+    };
+    self.drag = Some(Drag {
+      src: hit.zone,
+      depth,
+      grab_offset: pos.saturating_sub(corner),
+      pos: None,
+      was_selected: selected == Some(hit.zone),
+    });
+    self.cursor_src = Some(idx);
+    EventResult::consumed()
+  }
+
+  /// The cards being carried by the mouse, in column order: the one
+  /// underneath first and the one on top last, like they get drawn.
+  pub fn dragged_cards(&self) -> Vec<Card> {
+    let Some(drag) = &self.drag else {
+      return Vec::new();
+    };
+    match drag.src {
+      BoardZone::Column(col_idx) => {
+        self.board().get_column(col_idx)[drag.depth..].to_vec()
+      }
+      _ => self
+        .board()
+        .minor_foundation_storage()
+        .cloned()
+        .into_iter()
+        .collect(),
+    }
+  }
+}
@@ -0,0 +1,135 @@
+use std::{env, fs, io, path::PathBuf};
+
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+
+/// Play teletarot in the terminal.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Args {
+  /// Don't slide cards around, just put them where they go.
+  #[arg(long)]
+  pub no_animation: bool,
+  /// How big to draw the cards.
+  #[arg(long, value_enum)]
+  pub layout: Option<Layout>,
+  /// Which colours to use: dark, light, colorblind, colorblind-light,
+  /// or the name of a theme in the themes folder of the config directory.
+  #[arg(long)]
+  pub theme: Option<String>,
+  /// A folder with card art to use instead of the usual art,
+  /// as a `cards.xp` and a `board.xp`.
+  #[arg(long, value_name = "DIR")]
+  pub art: Option<PathBuf>,
+  /// Draw everything with plain ASCII, for terminals that garble anything
+  /// fancier. Normally this is worked out from the locale.
+  #[arg(long)]
+  pub ascii: bool,
+}
+
+/// Settings from `config.toml` in the teletarot config directory.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+  /// Whether cards slide around the board when they move.
+  pub animation: bool,
+  pub layout: Layout,
+  /// See [`crate::colors::Palette`].
+  pub theme: String,
+  /// Where to load an art pack from, if not using the built-in art.
+  pub art: Option<PathBuf>,
+  /// Whether to stick to ASCII. If it's not set, it's whether the locale
+  /// isn't UTF-8.
+  pub ascii: Option<bool>,
+}
+
+/// Whether to draw full-size cards or mini ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+  /// Full-size cards if they fit in the terminal, mini ones if not.
+  Auto,
+  Full,
+  Compact,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      animation: true,
+      layout: Layout::Auto,
+      theme: "dark".to_string(),
+      art: None,
+      ascii: None,
+    }
+  }
+}
+
+impl Settings {
+  pub fn config_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("teletarot"))
+  }
+
+  /// Load the settings file, and apply the command line on top of it.
+  ///
+  /// If the file is broken, the defaults get used and the error comes back
+  /// alongside them so it can be shown.
+  pub fn load(args: &Args) -> (Self, Option<String>) {
+    let (mut settings, error) = match Self::read_file() {
+      Ok(it) => (it, None),
+      Err(ono) => (Self::default(), Some(ono)),
+    };
+    if args.no_animation {
+      settings.animation = false;
+    }
+    if let Some(layout) = args.layout {
+      settings.layout = layout;
+    }
+    if let Some(theme) = &args.theme {
+      settings.theme = theme.clone();
+    }
+    if let Some(art) = &args.art {
+      settings.art = Some(art.clone());
+    }
+    if args.ascii {
+      settings.ascii = Some(true);
+    }
+    (settings, error)
+  }
+
+  pub fn ascii(&self) -> bool {
+    self.ascii.unwrap_or_else(|| !locale_is_utf8())
+  }
+
+  fn read_file() -> Result<Self, String> {
+    let Some(path) = Self::config_dir().map(|dir| dir.join("config.toml"))
+    else {
+      return Ok(Self::default());
+    };
+    let text = match fs::read_to_string(&path) {
+      Ok(it) => it,
+      Err(ono) if ono.kind() == io::ErrorKind::NotFound => {
+        return Ok(Self::default())
+      }
+      Err(ono) => return Err(format!("couldn't read {:?}: {}", path, ono)),
+    };
+    toml::from_str(&text).map_err(|ono| format!("in {:?}: {}", path, ono))
+  }
+}
+
+/// Guess whether the terminal can show Unicode, the way the C library would:
+/// from the first of `LC_ALL`, `LC_CTYPE` and `LANG` that's set.
+fn locale_is_utf8() -> bool {
+  // Windows terminals don't do locales like this, and they're fine
+  if cfg!(windows) {
+    return true;
+  }
+  let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+    .into_iter()
+    .filter_map(|var| env::var(var).ok())
+    .find(|it| !it.is_empty());
+  locale.is_some_and(|it| {
+    let it = it.to_ascii_lowercase();
+    it.contains("utf-8") || it.contains("utf8")
+  })
+}
@@ -1,31 +1,62 @@
-use std::io;
+use std::{array, fs::File, io, path::Path};
 
 use codepage_437::CP437_WINGDINGS;
 use cursive::{
-  theme::{BaseColor, Color, ColorPair, Style},
-  utils::markup::{StyledStr, StyledString},
-  vec::Vec2i,
-  Printer, Vec2, With,
+  theme::{BaseColor, Color, ColorPair, Effect, Style},
+  utils::markup::StyledString,
+  Printer, Rect, Vec2, With,
 };
 use getset::{CopyGetters, Getters};
 use itertools::iproduct;
 use rexpaint::{XpCell, XpColor, XpFile, XpLayer};
-use teletarot_model::{Board, Card, Suit};
+use teletarot_model::{Board, Card, Column, MinorSuit, Suit};
 
-use crate::{boxes::BoxSide, colors, fg_color};
+use crate::{boxes::BoxSide, colors::Palette};
 
-const CARD_XP_INCLUDE: &'static [u8] = include_bytes!("include/cards.xp");
-const LAYOUT_XP_INCLUDE: &'static [u8] = include_bytes!("include/board.xp");
+const CARD_XP_INCLUDE: &[u8] = include_bytes!("include/cards.xp");
+const LAYOUT_XP_INCLUDE: &[u8] = include_bytes!("include/board.xp");
+
+/// What the files in an art pack are called.
+const PACK_CARDS: &str = "cards.xp";
+const PACK_LAYOUT: &str = "board.xp";
 
 pub const CARD_WIDTH: usize = 11;
 pub const CARD_HEIGHT: usize = 10;
-pub const CARD_PADDING: usize = 1;
 pub const CARD_SIZE: Vec2 = Vec2::new(CARD_WIDTH, CARD_HEIGHT);
+/// The card art is 13 cards across, with a row for each minor suit and two
+/// for the major arcana, and a cell of space around each card.
+const CARD_SHEET_SIZE: Vec2 =
+  Vec2::new(13 * (CARD_WIDTH + 1) + 1, 6 * (CARD_HEIGHT + 1) + 1);
+
+/// Mini cards are just the card's name, like ` 7c` or `10p`.
+pub const MINI_CARD_WIDTH: usize = 3;
+/// How many cards fit in a column of the compact layout before it runs
+/// off the bottom.
+const MINI_COLUMN_ROWS: usize = 16;
 
+/// Where and how to draw everything on the board.
+///
+/// There's a full-size one with card art, laid out by `board.xp`,
+/// and a compact one with mini cards for small terminals.
 #[derive(Getters, CopyGetters)]
 pub struct CardAtlas {
-  cards_xp: XpFile,
-  layout_display: XpLayer,
+  /// The card art. Compact atlases don't have any.
+  cards_xp: Option<XpFile>,
+  layout_display: Option<XpLayer>,
+  #[getset(get = "pub")]
+  palette: Palette,
+
+  #[getset(get_copy = "pub")]
+  compact: bool,
+  /// Draw letters instead of the card art, and plain ASCII for everything.
+  #[getset(get_copy = "pub")]
+  ascii: bool,
+  /// The size of a drawn card, including its border.
+  #[getset(get_copy = "pub")]
+  card_box: Vec2,
+  /// How far down each card in a column is from the one before.
+  #[getset(get_copy = "pub")]
+  fan_step: usize,
 
   #[getset(get_copy = "pub")]
   maj_fndn_left: Vec2,
@@ -42,12 +73,74 @@ pub struct CardAtlas {
 }
 
 impl CardAtlas {
-  pub fn new() -> Self {
+  /// The full-size atlas with the art that comes with the game.
+  pub fn new(palette: Palette, ascii: bool) -> Self {
     let cards_xp = XpFile::read(&mut io::Cursor::new(CARD_XP_INCLUDE)).unwrap();
 
     let layout_xp =
       XpFile::read(&mut io::Cursor::new(LAYOUT_XP_INCLUDE)).unwrap();
 
+    Self::from_xp(cards_xp, layout_xp, palette, ascii)
+      .expect("the built-in art should be valid")
+  }
+
+  /// Load an art pack: a folder with a `cards.xp` and a `board.xp` in it,
+  /// drawn like the ones that come with the game.
+  pub fn load_pack(
+    dir: &Path,
+    palette: Palette,
+    ascii: bool,
+  ) -> Result<Self, String> {
+    let read = |name: &str| {
+      let path = dir.join(name);
+      File::open(&path)
+        .and_then(|file| XpFile::read(&mut io::BufReader::new(file)))
+        .map_err(|ono| format!("couldn't read {:?}: {}", path, ono))
+    };
+    let cards_xp = read(PACK_CARDS)?;
+    let layout_xp = read(PACK_LAYOUT)?;
+    Self::from_xp(cards_xp, layout_xp, palette, ascii)
+      .map_err(|ono| format!("{:?} isn't a good art pack: {}", dir, ono))
+  }
+
+  fn from_xp(
+    cards_xp: XpFile,
+    layout_xp: XpFile,
+    palette: Palette,
+    ascii: bool,
+  ) -> Result<Self, String> {
+    if cards_xp.layers.is_empty() {
+      return Err(format!("{} has no layers", PACK_CARDS));
+    }
+    for layer in &cards_xp.layers {
+      let size = Vec2::new(layer.width, layer.height);
+      if size != CARD_SHEET_SIZE {
+        return Err(format!(
+          "{} is {}x{}, but it should be {}x{}: 13 cards across and 6 down, \
+           each {}x{} with a gap between them",
+          PACK_CARDS,
+          size.x,
+          size.y,
+          CARD_SHEET_SIZE.x,
+          CARD_SHEET_SIZE.y,
+          CARD_WIDTH,
+          CARD_HEIGHT,
+        ));
+      }
+    }
+
+    let [layout_display, layout_layer, ..] = &layout_xp.layers[..] else {
+      return Err(format!(
+        "{} needs two layers, the picture and the layout markers",
+        PACK_LAYOUT
+      ));
+    };
+    if (layout_display.width, layout_display.height)
+      != (layout_layer.width, layout_layer.height)
+    {
+      return Err(format!("{}'s layers are different sizes", PACK_LAYOUT));
+    }
+
     let mut maj_fndn_left = None;
     let mut maj_fndn_right = None;
     let mut min_fndn_storage = None;
@@ -55,55 +148,220 @@ impl CardAtlas {
     let mut column_poses = [None; Board::COLUMN_COUNT];
     let mut board_size = None;
 
-    let layout_layer = &layout_xp.layers[1];
     for (idx, cell) in layout_layer.cells.iter().enumerate() {
       // pos is backwards from idx. huuahrhg
       let pos = Vec2::new(idx / layout_layer.height, idx % layout_layer.height);
 
-      match cell.ch as u8 {
-        b'L' => {
-          maj_fndn_left = Some(pos);
-        }
-        b'R' => {
-          maj_fndn_right = Some(pos);
-        }
-        0x0F => {
-          min_fndn_poses[0] = Some(pos);
-        }
-        0x9D => {
-          min_fndn_poses[1] = Some(pos);
-        }
-        0x18 => {
-          min_fndn_poses[2] = Some(pos);
-        }
-        b'%' => {
-          min_fndn_poses[3] = Some(pos);
-        }
-        b'S' => {
-          min_fndn_storage = Some(pos);
-        }
-        it @ b'a'..=b'k' => {
-          column_poses[(it - b'a') as usize] = Some(pos);
-        }
-        b'X' => {
-          board_size = Some(pos + (1, 1));
-        }
-        _ => {}
+      let slot = match cell.ch as u8 {
+        b'L' => &mut maj_fndn_left,
+        b'R' => &mut maj_fndn_right,
+        0x0F => &mut min_fndn_poses[0],
+        0x9D => &mut min_fndn_poses[1],
+        0x18 => &mut min_fndn_poses[2],
+        b'%' => &mut min_fndn_poses[3],
+        b'S' => &mut min_fndn_storage,
+        it @ b'a'..=b'k' => &mut column_poses[(it - b'a') as usize],
+        b'X' => &mut board_size,
+        _ => continue,
+      };
+      if slot.is_some() {
+        return Err(format!(
+          "{} has more than one {:?} marker",
+          PACK_LAYOUT,
+          CP437_WINGDINGS.decode(cell.ch as u8)
+        ));
       }
+      *slot = Some(pos);
     }
 
-    Self {
-      cards_xp,
-      layout_display: layout_xp.layers[0].clone(),
-      maj_fndn_left: maj_fndn_left.unwrap(),
-      maj_fndn_right: maj_fndn_right.unwrap(),
-      min_fndn_storage: min_fndn_storage.unwrap(),
+    let missing = |marker: u8| {
+      format!(
+        "{} is missing the {:?} marker",
+        PACK_LAYOUT,
+        CP437_WINGDINGS.decode(marker)
+      )
+    };
+    let board_size = board_size.ok_or_else(|| missing(b'X'))? + (1, 1);
+    if let Some(idx) = min_fndn_poses.iter().position(Option::is_none) {
+      return Err(missing([0x0F, 0x9D, 0x18, b'%'][idx]));
+    }
+    if let Some(idx) = column_poses.iter().position(Option::is_none) {
+      return Err(missing(b'a' + idx as u8));
+    }
+    let atlas = Self {
+      cards_xp: Some(cards_xp),
+      layout_display: Some(layout_display.clone()),
+      palette,
+      compact: false,
+      ascii,
+      card_box: CARD_SIZE + (2, 2),
+      fan_step: 2,
+      maj_fndn_left: maj_fndn_left.ok_or_else(|| missing(b'L'))?,
+      maj_fndn_right: maj_fndn_right.ok_or_else(|| missing(b'R'))?,
+      min_fndn_storage: min_fndn_storage.ok_or_else(|| missing(b'S'))?,
       min_fndn_poses: min_fndn_poses.map(Option::unwrap),
       column_poses: column_poses.map(Option::unwrap),
-      board_size: board_size.unwrap(),
+      board_size,
+    };
+
+    // Everything has to fit on the board, or it'll get cut off
+    let mut corners = vec![
+      atlas.maj_fndn_left + atlas.card_box,
+      atlas.maj_fndn_right,
+      atlas.min_fndn_storage + atlas.card_box,
+    ];
+    corners.extend(atlas.min_fndn_poses.map(|pos| pos + atlas.card_box));
+    corners.extend(atlas.column_poses.map(|pos| pos + atlas.card_box));
+    if !corners.iter().all(|corner| corner.fits_in(board_size)) {
+      return Err(format!(
+        "{} has cards that go past the 'X' marking the corner of the board",
+        PACK_LAYOUT
+      ));
+    }
+
+    Ok(atlas)
+  }
+
+  /// Lay out the board for small terminals, with mini cards.
+  ///
+  /// The foundations and storage go along the top row, and the columns
+  /// go underneath, one row per card.
+  pub fn new_compact(palette: Palette, ascii: bool) -> Self {
+    let step = MINI_CARD_WIDTH + 1;
+    let minors_x = 3 * step + 1;
+    Self {
+      cards_xp: None,
+      layout_display: None,
+      palette,
+      compact: true,
+      ascii,
+      card_box: Vec2::new(MINI_CARD_WIDTH, 1),
+      fan_step: 1,
+      maj_fndn_left: Vec2::new(0, 0),
+      maj_fndn_right: Vec2::new(2 * step, 0),
+      min_fndn_poses: array::from_fn(|idx| Vec2::new(minors_x + idx * step, 0)),
+      min_fndn_storage: Vec2::new(minors_x + 4 * step + 1, 0),
+      column_poses: array::from_fn(|idx| Vec2::new(idx * step, 2)),
+      board_size: Vec2::new(
+        Board::COLUMN_COUNT * step - 1,
+        2 + MINI_COLUMN_ROWS,
+      ),
+    }
+  }
+
+  /// Where the given card of a column gets drawn.
+  pub fn card_pos(
+    &self,
+    col_idx: usize,
+    col: &Column,
+    card_idx: usize,
+  ) -> Vec2 {
+    self.column_poses[col_idx]
+      + (0, self.column_offsets(col_idx, col)[card_idx])
+  }
+
+  /// How far down from the top of the column each of its cards goes.
+  ///
+  /// If a column would run off the bottom of the board, the cards buried
+  /// under its movable run get squashed together, deepest first, so the
+  /// run and the top card always stay on screen. Only if the run itself is
+  /// too long does it get squashed too.
+  pub fn column_offsets(&self, col_idx: usize, col: &Column) -> Vec<usize> {
+    let room = (self.board_size.y - self.column_poses[col_idx].y)
+      .saturating_sub(self.card_box.y);
+    let buried = col.len() - col.movable_run_len();
+
+    // How many rows of each card show above the one on top of it
+    let mut gaps = vec![self.fan_step; col.len().saturating_sub(1)];
+    let split = buried.min(gaps.len());
+    let (buried_gaps, run_gaps) = gaps.split_at_mut(split);
+    let run_height = run_gaps.len() * self.fan_step;
+    if run_height > room {
+      squash(buried_gaps, 0);
+      squash(run_gaps, room);
+    } else if buried_gaps.len() * self.fan_step > room - run_height {
+      squash(buried_gaps, room - run_height);
+    }
+
+    let mut offset = 0;
+    let mut offsets = vec![0];
+    for gap in gaps {
+      offset += gap;
+      offsets.push(offset);
+    }
+    offsets.truncate(col.len());
+    offsets
+  }
+
+  /// Where the given card on the left of the major foundation gets drawn.
+  ///
+  /// The final card goes in the middle, over both sides.
+  pub fn major_left_pos(&self, idx: usize, is_final: bool) -> Vec2 {
+    if self.compact {
+      return if is_final {
+        (self.maj_fndn_left + self.maj_fndn_right) / 2
+      } else {
+        self.maj_fndn_left
+      };
+    }
+    self.maj_fndn_left + (idx + if is_final { CARD_WIDTH / 2 } else { 0 }, 0)
+      - (0, if is_final { 1 } else { 0 })
+  }
+
+  /// Where the given card on the right of the major foundation gets drawn,
+  /// counting from the outside in.
+  pub fn major_right_pos(&self, idx: usize) -> Vec2 {
+    if self.compact {
+      return self.maj_fndn_right;
+    }
+    self.maj_fndn_right - (CARD_WIDTH + idx + 1, 0)
+  }
+
+  /// The area the major foundation can take up.
+  pub fn major_rect(&self) -> Rect {
+    let left = self.major_left_pos(0, false);
+    let right = self.maj_fndn_right;
+    if self.compact {
+      return Rect::from_corners(left, right + (MINI_CARD_WIDTH - 1, 0));
+    }
+    // the final card pokes up one row above the rest
+    Rect::from_corners(
+      left.saturating_sub((0, 1)),
+      (
+        right.x.max(left.x + CARD_WIDTH + 2),
+        left.y + CARD_HEIGHT + 1,
+      ),
+    )
+  }
+
+  /// Draw the outline of an empty place for a card.
+  pub fn print_slot(
+    &self,
+    printer: &Printer,
+    pos: impl Into<Vec2>,
+    style: Style,
+    thick: bool,
+  ) {
+    if self.compact {
+      let text = if thick { "[ ]" } else { " . " };
+      printer.print_styled(pos, &StyledString::styled(text, style));
+    } else {
+      BoxSide::draw_box(printer, pos, self.card_box, style, thick, self.ascii);
     }
   }
 
+  /// Blank out the face of a card, so whatever's under it doesn't show.
+  pub fn print_blank(&self, printer: &Printer, pos: impl Into<Vec2>) {
+    let face = if self.compact {
+      Rect::from_size(pos, self.card_box)
+    } else {
+      Rect::from_size(pos.into() + (1, 1), CARD_SIZE)
+    };
+    printer.with_color(Color::Light(BaseColor::Black).into(), |prn| {
+      prn.print_rect(face, " ");
+    });
+  }
+
   /// Draw a card, including the border, at the given position.
   pub fn print_card(
     &self,
@@ -115,17 +373,35 @@ impl CardAtlas {
   ) {
     let card_corner = card_corner.into();
 
-    let suit_color = colors::suit_color(card.suit());
+    let suit = card.suit();
     let border_style = match border_color {
-      CardBorderColor::HilightIfThickBorder => fg_color(if thick_border {
-        Color::Light
-      } else {
-        Color::Dark
-      }(suit_color)),
-      CardBorderColor::AlwaysHilight => fg_color(Color::Light(suit_color)),
-      CardBorderColor::NeverHilight => fg_color(Color::Dark(suit_color)),
+      CardBorderColor::HilightIfThickBorder => {
+        self.palette.suit_style(suit, thick_border)
+      }
+      CardBorderColor::AlwaysHilight => self.palette.suit_style(suit, true),
+      CardBorderColor::NeverHilight => self.palette.suit_style(suit, false),
       CardBorderColor::Custom(it) => it,
     };
+    // The letters already say what suit it is
+    let glyph = self.palette.glyph(suit).filter(|_| !self.ascii);
+
+    let Some(cards_xp) = &self.cards_xp else {
+      let mut style = border_style;
+      if thick_border {
+        style = style.combine(Effect::Reverse);
+      }
+      let mut name = card.to_string();
+      if let Some(glyph) = glyph {
+        // Swap the suit's letter for it
+        name.pop();
+        name.push(glyph);
+      }
+      printer.print_styled(
+        card_corner,
+        &StyledString::styled(format!("{:>3}", name), style),
+      );
+      return;
+    };
 
     BoxSide::draw_box(
       printer,
@@ -133,7 +409,12 @@ impl CardAtlas {
       CARD_SIZE + Vec2::new(2, 2),
       border_style,
       thick_border,
+      self.ascii,
     );
+    if self.ascii {
+      self.print_letter_face(card, card_corner + (1, 1), printer);
+      return;
+    }
 
     let atlas_idxes = match card.suit() {
       Suit::Minor(suit) => Vec2::new(card.number() as usize - 1, suit as _),
@@ -143,18 +424,126 @@ impl CardAtlas {
     };
     for (dy, dx) in iproduct!(0..CARD_HEIGHT, 0..CARD_WIDTH) {
       let src_pos = (atlas_idxes * (CARD_SIZE + (1, 1))) + (1, 1) + (dx, dy);
-      let cell = get_xp_thru_layers(&self.cards_xp, src_pos.x, src_pos.y);
-      draw_xp_cell(cell, card_corner + (dx + 1, dy + 1), printer);
+      let cell = get_xp_thru_layers(cards_xp, src_pos.x, src_pos.y);
+      self.draw_xp_cell(
+        cell,
+        Some(suit),
+        card_corner + (dx + 1, dy + 1),
+        printer,
+      );
+    }
+
+    // In opposite corners of the border, so it shows when fanned out
+    if let Some(glyph) = glyph {
+      let glyph = StyledString::styled(glyph.to_string(), border_style);
+      printer.print_styled(card_corner + (1, 0), &glyph);
+      printer.print_styled(card_corner + CARD_SIZE + (0, 1), &glyph);
     }
   }
 
+  /// Draw a card's face with its name instead of the art:
+  ///
+  /// ```text
+  /// Qc
+  ///
+  ///
+  ///
+  ///      Q
+  ///    cups
+  ///
+  ///
+  ///
+  ///          Qc
+  /// ```
+  fn print_letter_face(&self, card: &Card, corner: Vec2, printer: &Printer) {
+    let style = self.palette.suit_style(card.suit(), false);
+    let name = card.to_string();
+    // Everything but the suit letter
+    let rank = &name[..name.len() - 1];
+    let suit = match card.suit() {
+      Suit::Minor(MinorSuit::Pentacles) => "pentacles",
+      Suit::Minor(MinorSuit::Cups) => "cups",
+      Suit::Minor(MinorSuit::Swords) => "swords",
+      Suit::Minor(MinorSuit::Wands) => "wands",
+      Suit::MajorArcana => "arcana",
+    };
+    let lines = [
+      (0, format!("{:<CARD_WIDTH$}", name)),
+      (CARD_HEIGHT / 2 - 1, format!("{:^CARD_WIDTH$}", rank)),
+      (CARD_HEIGHT / 2, format!("{:^CARD_WIDTH$}", suit)),
+      (CARD_HEIGHT - 1, format!("{:>CARD_WIDTH$}", name)),
+    ];
+
+    printer.with_style(style, |prn| {
+      // Cover up whatever it's on top of
+      prn.print_rect(Rect::from_size(corner, CARD_SIZE), " ");
+      for (dy, line) in lines {
+        prn.print(corner + (0, dy), &line);
+      }
+    });
+  }
+
   pub fn print_background(&self, printer: &Printer) {
-    for (idx, cell) in self.layout_display.cells.iter().enumerate() {
-      let x = idx / self.layout_display.height;
-      let y = idx % self.layout_display.height;
-      draw_xp_cell(cell.clone(), (x, y), printer);
+    let Some(layout_display) = &self.layout_display else {
+      // Just a line between the foundations and the columns
+      let line = if self.ascii { "-" } else { "─" };
+      printer.with_color(self.palette.border.into(), |prn| {
+        prn.print_hline((0, 1), self.board_size.x, line);
+      });
+      return;
+    };
+    for (idx, cell) in layout_display.cells.iter().enumerate() {
+      let x = idx / layout_display.height;
+      let y = idx % layout_display.height;
+      self.draw_xp_cell(*cell, None, (x, y), printer);
     }
   }
+
+  /// Draw an XP cell from the given position from the xp file,
+  /// in the theme's colours.
+  fn draw_xp_cell(
+    &self,
+    cell: XpCell,
+    suit: Option<Suit>,
+    dest: impl Into<Vec2>,
+    printer: &Printer,
+  ) {
+    let siv_col = self.palette.art_color(color_xp_to_siv(cell.fg), suit);
+    let mut ch = CP437_WINGDINGS.decode(cell.ch as u8);
+    let mut colors = ColorPair::terminal_default().with(|x| x.front = siv_col);
+    if self.ascii {
+      if ch == '█' {
+        // A space in the same colour looks just the same
+        colors.back = siv_col;
+      }
+      ch = ascii_lookalike(ch);
+    }
+    printer
+      .print_styled(dest.into(), &StyledString::styled(ch.to_string(), colors));
+  }
+}
+
+/// Something in ASCII that looks a bit like the character.
+fn ascii_lookalike(ch: char) -> char {
+  match ch {
+    _ if ch.is_ascii() => ch,
+    '─' | '━' | '═' | '┄' | '┈' => '-',
+    '│' | '┃' | '║' | '┆' | '┊' => '|',
+    // The rest of the box drawing characters are all corners and joins
+    '\u{2500}'..='\u{257F}' => '+',
+    '█' => ' ',
+    '▓' | '▒' => '#',
+    '░' => ':',
+    '«' => '<',
+    '»' => '>',
+    '↑' => '^',
+    '↓' => 'v',
+    '←' => '<',
+    '→' => '>',
+    '¥' => 'Y',
+    '·' | '•' | '∙' => '.',
+    _ => '*',
+  }
 }
 
 fn color_xp_to_siv(color: XpColor) -> Color {
@@ -178,28 +567,34 @@ fn color_xp_to_siv(color: XpColor) -> Color {
     (255, 85, 255) => Light(Magenta),
     (85, 255, 255) => Light(Cyan),
     (255, 255, 255) => Light(White),
-    ono => unreachable!("forgot to account for xp color {:?}", ono),
+    // Art packs might not stick to the 16 colours
+    (r, g, b) => Rgb(r, g, b),
   }
 }
 
+#[derive(Clone, Copy)]
 pub enum CardBorderColor {
   HilightIfThickBorder,
   AlwaysHilight,
+  #[allow(dead_code)]
   NeverHilight,
   Custom(Style),
 }
 
-/// Draw an XP cell from the given position from the xp file.
-pub fn draw_xp_cell(cell: XpCell, dest: impl Into<Vec2>, printer: &Printer) {
-  let siv_col = color_xp_to_siv(cell.fg);
-  let ch = CP437_WINGDINGS.decode(cell.ch as u8);
-  printer.print_styled(
-    dest.into(),
-    &StyledString::styled(
-      ch.to_string(),
-      ColorPair::terminal_default().with(|x| x.front = siv_col),
-    ),
-  );
+/// Shrink the gaps so they add up to `rows` at most, as evenly as possible.
+/// The leftover rows go to the gaps at the end, which are nearer the top of
+/// the column.
+fn squash(gaps: &mut [usize], rows: usize) {
+  let count = gaps.len();
+  if count == 0 {
+    return;
+  }
+  let each = rows / count;
+  let extra = rows % count;
+  for (idx, gap) in gaps.iter_mut().enumerate() {
+    let share = each + usize::from(idx >= count - extra);
+    *gap = (*gap).min(share);
+  }
 }
 
 fn get_xp_thru_layers(xp: &XpFile, x: usize, y: usize) -> XpCell {
@@ -0,0 +1,71 @@
+use std::{
+  io::{self, BufRead},
+  process::ExitCode,
+};
+
+use teletarot_model::game::Game;
+
+use crate::{command::Command, display::BoardPrinter};
+
+/// Play the moves on stdin, one per line, without a prompt.
+///
+/// Blank lines and lines starting with `#` are skipped. It stops at the
+/// first move that doesn't work and prints the board as it was left.
+///
+/// Exits with 0 if the board got solved, 1 if it didn't,
+/// and 2 if a line couldn't be played.
+pub fn run(mut game: Game, printer: &BoardPrinter) -> ExitCode {
+  let mut stopped = false;
+  for (idx, line) in io::stdin().lock().lines().enumerate() {
+    let line = match line {
+      Ok(it) => it,
+      Err(ono) => {
+        eprintln!("couldn't read the moves: {}", ono);
+        stopped = true;
+        break;
+      }
+    };
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let result = match Command::parse(line) {
+      Ok(Command::Move(src, dst)) => game
+        .move_card(src, dst)
+        .map_err(|ono| format!("You can't do that: {}", ono)),
+      Ok(Command::Automove) => {
+        game.check_automove_cards();
+        Ok(())
+      }
+      Ok(Command::Undo) if game.undo() => Ok(()),
+      Ok(Command::Undo) => Err("Nothing to undo".to_string()),
+      Ok(Command::Quit) => break,
+      Ok(
+        Command::Solve(_)
+        | Command::Next
+        | Command::Prev
+        | Command::Pause
+        | Command::Stats
+        | Command::Layout(_),
+      ) => Err("only moves, `z`, `u` and `q` work on stdin".to_string()),
+      Err(ono) => Err(ono),
+    };
+    if let Err(ono) = result {
+      eprintln!("line {}: {:?}: {}", idx + 1, line, ono);
+      stopped = true;
+      break;
+    }
+  }
+
+  printer.print(game.board());
+  if stopped {
+    ExitCode::from(2)
+  } else if game.is_solved() {
+    println!("Solved in {} moves.", game.moves());
+    ExitCode::SUCCESS
+  } else {
+    println!("Not solved after {} moves.", game.moves());
+    ExitCode::FAILURE
+  }
+}
@@ -0,0 +1,87 @@
+use clap::ValueEnum;
+use teletarot_model::BoardZone;
+
+use crate::display::Layout;
+
+/// Everything you can type that isn't a move.
+pub const COMMANDS: [&str; 13] = [
+  "solve",
+  "solve --dry-run",
+  "solve --step",
+  "next",
+  "prev",
+  "z",
+  "u",
+  "p",
+  "stats",
+  "layout",
+  "layout rows",
+  "layout columns",
+  "q",
+];
+
+/// A line typed in at the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+  Move(BoardZone, BoardZone),
+  Solve(SolveMode),
+  /// Play the next move of the solution being stepped through.
+  Next,
+  /// Take back the last move of the solution being stepped through.
+  Prev,
+  Automove,
+  Undo,
+  Pause,
+  Stats,
+  /// Switch to the given layout, or the other one.
+  Layout(Option<Layout>),
+  Quit,
+}
+
+/// What to do with the solver's solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveMode {
+  /// Print it, then play it all.
+  Apply,
+  /// Just print it.
+  DryRun,
+  /// Play it a move at a time with `next` and `prev`.
+  Step,
+}
+
+impl Command {
+  pub fn parse(line: &str) -> Result<Self, String> {
+    match line {
+      "solve" => return Ok(Command::Solve(SolveMode::Apply)),
+      "solve --dry-run" => return Ok(Command::Solve(SolveMode::DryRun)),
+      "solve --step" => return Ok(Command::Solve(SolveMode::Step)),
+      "next" => return Ok(Command::Next),
+      "prev" => return Ok(Command::Prev),
+      "z" => return Ok(Command::Automove),
+      "u" => return Ok(Command::Undo),
+      "p" => return Ok(Command::Pause),
+      "stats" => return Ok(Command::Stats),
+      "q" => return Ok(Command::Quit),
+      "layout" => return Ok(Command::Layout(None)),
+      _ => {}
+    }
+    if let Some(name) = line.strip_prefix("layout ") {
+      return Layout::from_str(name, false)
+        .map(|it| Command::Layout(Some(it)))
+        .map_err(|_| "the layouts are `rows` and `columns`".to_string());
+    }
+
+    let Some((src_s, dst_s)) = line.split_once(' ') else {
+      return Err(
+        "please write a source and dest separated by a space".to_string(),
+      );
+    };
+    let src = src_s
+      .parse::<BoardZone>()
+      .map_err(|_| "source zone was written invalid".to_string())?;
+    let dst = dst_s
+      .parse::<BoardZone>()
+      .map_err(|_| "destination zone was written invalid".to_string())?;
+    Ok(Command::Move(src, dst))
+  }
+}
@@ -0,0 +1,236 @@
+use std::{
+  env,
+  io::{self, IsTerminal},
+};
+
+use clap::ValueEnum;
+use itertools::Itertools;
+use teletarot_model::{Board, Card, MinorSuit, Suit};
+
+/// Whether to colour the board in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+  /// If stdout's a terminal and `NO_COLOR` isn't set.
+  Auto,
+  Always,
+  Never,
+}
+
+impl ColorChoice {
+  pub fn should_color(self) -> bool {
+    match self {
+      ColorChoice::Auto => {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|it| !it.is_empty());
+        io::stdout().is_terminal() && !no_color
+      }
+      ColorChoice::Always => true,
+      ColorChoice::Never => false,
+    }
+  }
+}
+
+/// How to lay the board out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Layout {
+  /// Each column on its own row, top card on the right.
+  Rows,
+  /// The columns side by side going down, top card at the bottom,
+  /// like the cards on the table.
+  Columns,
+}
+
+impl Layout {
+  pub fn other(self) -> Self {
+    match self {
+      Layout::Rows => Layout::Columns,
+      Layout::Columns => Layout::Rows,
+    }
+  }
+}
+
+/// How much a card should stand out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Emphasis {
+  Normal,
+  /// It's the next card a foundation needs.
+  Playable,
+  /// It's on top of a foundation, or in the storage.
+  Top,
+}
+
+/// Prints the board as text, in colour if it's wanted.
+///
+/// The colours are the same as the cursive controller's.
+pub struct BoardPrinter {
+  pub color: bool,
+  pub layout: Layout,
+}
+
+impl BoardPrinter {
+  pub fn new(color: ColorChoice, layout: Layout) -> Self {
+    Self {
+      color: color.should_color(),
+      layout,
+    }
+  }
+
+  pub fn print(&self, board: &Board) {
+    match self.layout {
+      Layout::Rows => self.print_rows(board),
+      Layout::Columns => self.print_columns(board),
+    }
+  }
+
+  fn print_rows(&self, board: &Board) {
+    let maj_left = board.major_foundation_left_max();
+    let maj_right = board.major_foundation_right_min();
+    let maj = (Card::MAJOR_ARCANA_MIN..=Card::MAJOR_ARCANA_MAX)
+      .map(|idx| {
+        let has_left = match maj_left {
+          None => false,
+          Some(lo) => idx <= lo,
+        };
+        let has_right = match maj_right {
+          None => false,
+          Some(hi) => idx >= hi,
+        };
+        if has_left || has_right {
+          let is_top = Some(idx) == maj_left || Some(idx) == maj_right;
+          let emphasis = if is_top {
+            Emphasis::Top
+          } else {
+            Emphasis::Normal
+          };
+          self.paint(&format!("{:>2}", idx), Suit::MajorArcana, emphasis)
+        } else {
+          "..".to_string()
+        }
+      })
+      .join(" ");
+
+    let minor_maxes = board
+      .virtual_cards_minor_foundation()
+      .map(|v| {
+        let card = v.last();
+        match card {
+          Some(c) => self.card(c, Emphasis::Top),
+          None => "...".to_string(),
+        }
+      })
+      .join(" ");
+
+    let columns = (0..Board::COLUMN_COUNT)
+      .map(|col_idx| {
+        let row = board
+          .get_column(col_idx)
+          .iter()
+          .map(|card| self.tableau_card(board, card))
+          .join(" ");
+        format!("{:>2}. {}", col_idx, row)
+      })
+      .join("\n");
+
+    println!("~{{{}}}~", maj);
+    print!("[{}]", minor_maxes);
+    if let Some(store) = board.minor_foundation_storage() {
+      print!(" -[{}]-", self.card(store, Emphasis::Top));
+    }
+    println!();
+
+    println!("{}", columns);
+  }
+
+  /// Everything on one line up top, then the columns going down under it:
+  ///
+  /// ```text
+  /// ~{0-3 .. 20-21}~ [ 4p  Ac  As  Aw] -[ 9c]-
+  ///
+  ///   0   1   2   3   4   5   6   7   8   9  10
+  ///  8A  6w 20A  8s  6A      Ks  7p 17A  Kp 21A
+  ///  Jc  7s  Kw  3c  Jp      9A      Qw  Kc  7A
+  /// ```
+  fn print_columns(&self, board: &Board) {
+    let paint_run = |lo: u8, hi: u8| {
+      self.paint(&format!("{}-{}", lo, hi), Suit::MajorArcana, Emphasis::Top)
+    };
+    let maj_left = board
+      .major_foundation_left_max()
+      .map(|lo| paint_run(Card::MAJOR_ARCANA_MIN, lo));
+    let maj_right = board
+      .major_foundation_right_min()
+      .map(|hi| paint_run(hi, Card::MAJOR_ARCANA_MAX));
+    let maj = [maj_left, Some("..".to_string()), maj_right]
+      .into_iter()
+      .flatten()
+      .join(" ");
+    let minor_maxes = board
+      .virtual_cards_minor_foundation()
+      .map(|v| match v.last() {
+        Some(c) => self.card(c, Emphasis::Top),
+        None => "...".to_string(),
+      })
+      .join(" ");
+
+    print!("~{{{}}}~ [{}]", maj, minor_maxes);
+    if let Some(store) = board.minor_foundation_storage() {
+      print!(" -[{}]-", self.card(store, Emphasis::Top));
+    }
+    println!();
+    println!();
+
+    let header = (0..Board::COLUMN_COUNT)
+      .map(|col_idx| format!("{:>3}", col_idx))
+      .join(" ");
+    println!("{}", header);
+    let depth = board.columns().iter().map(|col| col.len()).max();
+    for row_idx in 0..depth.unwrap_or_default() {
+      let row = board
+        .columns()
+        .iter()
+        .map(|col| match col.get(row_idx) {
+          Some(card) => self.tableau_card(board, card),
+          None => "   ".to_string(),
+        })
+        .join(" ");
+      println!("{}", row.trim_end());
+    }
+  }
+
+  /// A card in the tableau, picked out if it's playable.
+  fn tableau_card(&self, board: &Board, card: &Card) -> String {
+    let emphasis = if board.is_next_for_foundation(card) {
+      Emphasis::Playable
+    } else {
+      Emphasis::Normal
+    };
+    self.card(card, emphasis)
+  }
+
+  /// A card's name, 3 wide.
+  fn card(&self, card: &Card, emphasis: Emphasis) -> String {
+    // note i have to call to_string because you have to wire up all
+    // the flags yourself when impling Display/Debug and I can't be assed
+    self.paint(&format!("{:>3}", card.to_string()), card.suit(), emphasis)
+  }
+
+  /// Wrap the text in the escape codes for the suit's colour.
+  fn paint(&self, text: &str, suit: Suit, emphasis: Emphasis) -> String {
+    if !self.color {
+      return text.to_string();
+    }
+    // Dark colours are 30 to 37, and the bright ones are 90 to 97
+    let base = match suit {
+      Suit::Minor(MinorSuit::Pentacles) => 3,
+      Suit::Minor(MinorSuit::Cups) => 1,
+      Suit::Minor(MinorSuit::Swords) => 6,
+      Suit::Minor(MinorSuit::Wands) => 2,
+      Suit::MajorArcana => 5,
+    };
+    let codes = match emphasis {
+      Emphasis::Normal => format!("{}", 30 + base),
+      Emphasis::Playable => format!("{}", 90 + base),
+      Emphasis::Top => format!("1;{}", 90 + base),
+    };
+    format!("\x1b[{}m{}\x1b[0m", codes, text)
+  }
+}
@@ -0,0 +1,124 @@
+use std::borrow::Cow;
+
+use rustyline::{
+  completion::{Completer, Pair},
+  highlight::Highlighter,
+  hint::Hinter,
+  validate::Validator,
+  Context, Helper,
+};
+use teletarot_model::{Board, BoardZone};
+
+use crate::command::COMMANDS;
+
+/// Tab completion and hints for the prompt.
+///
+/// It needs to know what the board looks like to hint at moves,
+/// so keep [`TeletarotHelper::board`] up to date.
+pub struct TeletarotHelper {
+  pub board: Board,
+  /// Whether hints get dimmed, like the board gets coloured.
+  color: bool,
+}
+
+impl TeletarotHelper {
+  pub fn new(board: Board, color: bool) -> Self {
+    Self { board, color }
+  }
+}
+
+impl Completer for TeletarotHelper {
+  type Candidate = Pair;
+
+  fn complete(
+    &self,
+    line: &str,
+    pos: usize,
+    _ctx: &Context<'_>,
+  ) -> rustyline::Result<(usize, Vec<Pair>)> {
+    let start = line[..pos].rfind(' ').map_or(0, |idx| idx + 1);
+    let word = &line[start..pos];
+
+    // The next word of any commands that start with what's been typed
+    let before = &line[..start];
+    let mut candidates = COMMANDS
+      .iter()
+      .filter_map(|it| it.strip_prefix(before))
+      .map(|rest| match rest.split_once(' ') {
+        Some((next, _)) => format!("{} ", next),
+        None => rest.to_string(),
+      })
+      .collect::<Vec<_>>();
+    candidates.dedup_by(|a, b| a.trim_end() == b.trim_end());
+
+    match before.trim().parse::<BoardZone>() {
+      // The first word could also be a zone to take a card from
+      _ if start == 0 => {
+        candidates.extend(BoardZone::all().map(|zone| zone.short_name() + " "));
+      }
+      // Then it's where to put it. Only bother with places it can go,
+      // unless it can't go anywhere
+      Ok(src) => {
+        let mut dsts = self.board.legal_destinations(src);
+        if dsts.is_empty() {
+          dsts = BoardZone::all().collect();
+        }
+        candidates.extend(dsts.iter().map(BoardZone::short_name));
+      }
+      Err(_) => {}
+    }
+
+    let pairs = candidates
+      .into_iter()
+      .filter(|it| it.starts_with(word))
+      .map(|it| Pair {
+        display: it.trim_end().to_string(),
+        replacement: it,
+      })
+      .collect();
+    Ok((start, pairs))
+  }
+}
+
+impl Hinter for TeletarotHelper {
+  type Hint = String;
+
+  /// Say where a card can go once its source has been typed,
+  /// and why not if the move typed in won't work.
+  fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<String> {
+    if pos < line.len() {
+      return None;
+    }
+    let (src, dst) = line.split_once(' ')?;
+    let src = src.parse::<BoardZone>().ok()?;
+
+    if dst.is_empty() {
+      let dsts = self.board.legal_destinations(src);
+      if dsts.is_empty() {
+        return Some("  (that can't go anywhere)".to_string());
+      }
+      let names = dsts.iter().map(BoardZone::short_name).collect::<Vec<_>>();
+      return Some(format!("  (to {})", names.join(", ")));
+    }
+
+    let dst = dst.parse::<BoardZone>().ok()?;
+    match self.board.clone().move_card(src, dst, true) {
+      Ok(()) => None,
+      Err(ono) => Some(format!("  ({})", ono)),
+    }
+  }
+}
+
+impl Highlighter for TeletarotHelper {
+  fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+    if !self.color {
+      return Cow::Borrowed(hint);
+    }
+    // Dim, so it doesn't look like it's been typed
+    Cow::Owned(format!("\x1b[2m{}\x1b[0m", hint))
+  }
+}
+
+impl Validator for TeletarotHelper {}
+
+impl Helper for TeletarotHelper {}
@@ -0,0 +1,170 @@
+use std::{
+  io::{self, BufRead},
+  process::ExitCode,
+};
+
+use serde_json::{json, Value};
+use teletarot_model::{game::Game, solver, BoardZone, CardMoveError};
+
+use crate::{
+  command::{Command, SolveMode},
+  solve::{self, Solution, Walkthrough},
+  stats_text,
+};
+
+/// Take the same commands as usual from stdin, but answer each with
+/// a line of JSON, for other programs to read.
+///
+/// Every command gets one object back saying how it went, with a `type`,
+/// followed by a `board` object with the board as it is afterwards.
+/// The first line is the board as it was dealt. Unlike `--batch`, it keeps
+/// going after a move that doesn't work.
+///
+/// Exits with 0 if the board got solved and 1 if it didn't.
+pub fn run(mut game: Game) -> ExitCode {
+  let mut walkthrough = None;
+  emit(board_json(&game));
+
+  for line in io::stdin().lock().lines() {
+    let line = match line {
+      Ok(it) => it,
+      Err(ono) => {
+        let message = format!("couldn't read the commands: {}", ono);
+        emit(json!({ "type": "error", "message": message }));
+        break;
+      }
+    };
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    if game.is_paused() {
+      game.resume();
+    }
+
+    let command = match Command::parse(line) {
+      Ok(it) => it,
+      Err(ono) => {
+        emit(json!({ "type": "error", "message": ono }));
+        continue;
+      }
+    };
+    if matches!(
+      command,
+      Command::Move(..) | Command::Automove | Command::Undo
+    ) {
+      walkthrough = None;
+    }
+
+    let reply = match command {
+      Command::Move(src, dst) => {
+        let result = game.move_card(src, dst);
+        json!({
+          "type": "move",
+          "from": src,
+          "to": dst,
+          "ok": result.is_ok(),
+          "error": result.err().as_ref().map(CardMoveError::explained),
+        })
+      }
+      Command::Solve(mode) => {
+        let soln = solver::try_solve(game.board());
+        walkthrough = None;
+        match (&soln, mode) {
+          (Some(soln), SolveMode::Apply) => solve::apply(&mut game, soln),
+          (Some(_), SolveMode::DryRun) | (None, _) => {}
+          (Some(soln), SolveMode::Step) => {
+            walkthrough = Some(Walkthrough::start(&mut game, soln.clone()));
+          }
+        }
+        solve_json(soln.as_ref(), mode)
+      }
+      Command::Next => match &mut walkthrough {
+        Some(steps) => {
+          let played = steps.next(&mut game);
+          json!({
+            "type": "next",
+            "ok": played.is_some(),
+            "move": played.map(|(src, dst)| move_json(src, dst)),
+            "step": steps.played(),
+            "steps": steps.total(),
+          })
+        }
+        None => no_walkthrough(),
+      },
+      Command::Prev => match &mut walkthrough {
+        Some(steps) => json!({
+          "type": "prev",
+          "ok": steps.prev(&mut game),
+          "step": steps.played(),
+          "steps": steps.total(),
+        }),
+        None => no_walkthrough(),
+      },
+      Command::Automove => {
+        game.check_automove_cards();
+        json!({ "type": "automove" })
+      }
+      Command::Undo => json!({ "type": "undo", "ok": game.undo() }),
+      Command::Pause => {
+        game.pause();
+        json!({ "type": "pause" })
+      }
+      Command::Stats => match stats_text(&game) {
+        Ok(text) => json!({ "type": "stats", "text": text }),
+        Err(ono) => json!({ "type": "error", "message": ono }),
+      },
+      // There's nothing to lay out
+      Command::Layout(_) => json!({ "type": "layout" }),
+      Command::Quit => break,
+    };
+    emit(reply);
+    emit(board_json(&game));
+  }
+
+  if game.is_solved() {
+    ExitCode::SUCCESS
+  } else {
+    ExitCode::FAILURE
+  }
+}
+
+fn emit(value: Value) {
+  println!("{}", value);
+}
+
+fn no_walkthrough() -> Value {
+  json!({ "type": "error", "message": "type `solve --step` first" })
+}
+
+fn move_json(src: BoardZone, dst: BoardZone) -> Value {
+  json!({ "from": src, "to": dst })
+}
+
+/// The model's summary of the game, which has the board in it.
+fn board_json(game: &Game) -> Value {
+  let mut value =
+    serde_json::to_value(game.summary()).expect("games can be JSON");
+  value["type"] = json!("board");
+  value
+}
+
+fn solve_json(soln: Option<&Solution>, mode: SolveMode) -> Value {
+  let mode = match mode {
+    SolveMode::Apply => "apply",
+    SolveMode::DryRun => "dry-run",
+    SolveMode::Step => "step",
+  };
+  let moves = soln.map(|soln| {
+    soln
+      .iter()
+      .map(|&(src, dst)| move_json(src, dst))
+      .collect::<Vec<_>>()
+  });
+  json!({
+    "type": "solve",
+    "mode": mode,
+    "found": soln.is_some(),
+    "moves": moves,
+  })
+}
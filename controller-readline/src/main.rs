@@ -1,9 +1,101 @@
-use itertools::Itertools;
-use teletarot_model::{Board, BoardZone, Card};
+use std::{
+  fs,
+  io::{self, IsTerminal},
+  path::PathBuf,
+  process::ExitCode,
+};
 
-fn main() {
-  let mut board = teletarot_model::random::smartish_random(None);
-  let mut rl = rustyline::DefaultEditor::new().unwrap();
+use clap::Parser;
+use command::{Command, SolveMode};
+use display::{BoardPrinter, ColorChoice, Layout};
+use helper::TeletarotHelper;
+use rustyline::{
+  error::ReadlineError, history::DefaultHistory, CompletionType, Config, Editor,
+};
+use solve::Walkthrough;
+use teletarot_model::{
+  game::Game,
+  random::Generator,
+  solver,
+  stats::{format_duration, Stats},
+};
+
+mod batch;
+mod command;
+mod display;
+mod helper;
+mod json;
+mod solve;
+
+/// Play teletarot by typing moves in.
+///
+/// If stdin isn't a terminal, the moves are read from it without a prompt,
+/// and the exit code says how it went: 0 if the board got solved, 1 if it
+/// didn't, and 2 if a move couldn't be played.
+///
+/// With `--json`, every board and result is printed as a line of JSON
+/// instead, for other programs to read.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+  /// Which deal to play.
+  #[arg(long)]
+  seed: Option<u64>,
+  /// How to deal the board: shuffled or smartish.
+  #[arg(long, default_value = "smartish", value_parser = parse_generator)]
+  generator: Generator,
+  /// Read moves from stdin without a prompt, even if it's a terminal.
+  #[arg(long)]
+  batch: bool,
+  /// Read commands from stdin and answer each with a line of JSON.
+  #[arg(long)]
+  json: bool,
+  /// Whether to colour the cards in by suit.
+  #[arg(long, value_enum, default_value = "auto")]
+  color: ColorChoice,
+  /// How to print the board. It can be switched while playing with `layout`.
+  #[arg(long, value_enum, default_value = "rows")]
+  layout: Layout,
+}
+
+fn parse_generator(s: &str) -> Result<Generator, String> {
+  s.parse().map_err(|()| {
+    let names = Generator::ALL.map(|it| it.name());
+    format!("should be one of {}", names.join(", "))
+  })
+}
+
+fn main() -> ExitCode {
+  let args = Args::parse();
+  let game = Game::new(args.generator, args.seed);
+  if args.json {
+    return json::run(game);
+  }
+  let printer = BoardPrinter::new(args.color, args.layout);
+  if args.batch || !io::stdin().is_terminal() {
+    return batch::run(game, &printer);
+  }
+  play(game, printer);
+  ExitCode::SUCCESS
+}
+
+fn play(mut game: Game, mut printer: BoardPrinter) {
+  // List the options like a shell does, instead of cycling through them
+  let config = Config::builder()
+    .completion_type(CompletionType::List)
+    .build();
+  let mut rl =
+    Editor::<TeletarotHelper, DefaultHistory>::with_config(config).unwrap();
+  let helper = TeletarotHelper::new(game.board().clone(), printer.color);
+  rl.set_helper(Some(helper));
+  let history = history_path();
+  if let Some(path) = &history {
+    match rl.load_history(path) {
+      Ok(()) => {}
+      Err(ReadlineError::Io(ono)) if ono.kind() == io::ErrorKind::NotFound => {}
+      Err(ono) => println!("Couldn't load your history: {}", ono),
+    }
+  }
 
   println!("Welcome to teletarot.");
   println!("Type where to take a card from and where to put it.");
@@ -11,136 +103,194 @@ fn main() {
   println!("- a: into the minor arcana foundation");
   println!("- A: into the major arcana foundation");
   println!("- s: the storage on top of the minor arcana");
+  println!("Type `u` to undo, `p` to pause the clock,");
+  println!("and `stats` to see how you've been doing.");
+  println!("`layout` switches between showing columns as rows or going down.");
+  println!("`solve` plays the rest of the game for you;");
+  println!("`solve --dry-run` just shows how, and with `solve --step`");
+  println!("you can go through it with `next` and `prev`.");
+  println!("Tab completes commands and zones.");
   println!();
 
+  let mut walkthrough = None;
   loop {
-    print_board(&board);
+    if game.is_solved() {
+      walkthrough = None;
+      finish_game(&game);
+      game = Game::new(game.generator(), None);
+      println!("Dealing a new game, seed {}.", game.seed());
+      println!();
+    }
+
+    printer.print(game.board());
     println!();
 
-    let line = match rl.readline("> ") {
+    if let Some(helper) = rl.helper_mut() {
+      helper.board = game.board().clone();
+    }
+    let line = match rl.readline(&prompt(&game)) {
       Ok(it) => it,
       Err(_) => break,
     };
-    if line == "solve" {
-      let soln = teletarot_model::solver::try_solve(&board);
-
-      if let Some(soln) = soln {
-        for (src, dst) in &soln {
-          println!("{:3} -> {:3}", src.short_name(), dst.short_name());
-        }
+    if !line.trim().is_empty() {
+      let _ = rl.add_history_entry(line.as_str());
+    }
+    if game.is_paused() {
+      game.resume();
+      println!("Unpaused.");
+      if line.is_empty() {
+        continue;
+      }
+    }
 
-        board.check_automove_cards();
-        for (src, dst) in soln {
-          let res = board.move_card(src, dst, true);
-          if let Err(ono) = res {
-            panic!(
-              "solver gave bad movement {:?} -> {:?}: {:?}",
-              src, dst, ono
+    let command = match Command::parse(&line) {
+      Ok(it) => it,
+      Err(ono) => {
+        println!("{}", ono);
+        continue;
+      }
+    };
+    // Once the board's been changed some other way the solution's no good
+    if matches!(
+      command,
+      Command::Move(..) | Command::Automove | Command::Undo
+    ) {
+      walkthrough = None;
+    }
+    match command {
+      Command::Solve(mode) => {
+        walkthrough = None;
+        let Some(soln) = solver::try_solve(game.board()) else {
+          println!("No solution!");
+          continue;
+        };
+        match mode {
+          SolveMode::Apply => {
+            solve::print_moves(&soln);
+            solve::apply(&mut game, &soln);
+          }
+          SolveMode::DryRun => {
+            solve::print_moves(&soln);
+            println!("That's {} moves. Nothing's been moved.", soln.len());
+          }
+          SolveMode::Step => {
+            println!(
+              "Found a solution in {} moves. Type `next` and `prev` to step \
+               through it.",
+              soln.len()
             );
+            walkthrough = Some(Walkthrough::start(&mut game, soln));
           }
-          board.check_automove_cards();
         }
-      } else {
-        println!("No solution!");
       }
-
-      continue;
-    } else if line == "z" {
-      board.check_automove_cards();
-      println!("Tried to automove cards");
-      continue;
-    } else if line == "q" {
-      println!("bye!");
-      break;
+      Command::Next => match &mut walkthrough {
+        Some(steps) => match steps.next(&mut game) {
+          Some((src, dst)) => println!(
+            "Step {}/{}: {} -> {}",
+            steps.played(),
+            steps.total(),
+            src.short_name(),
+            dst.short_name()
+          ),
+          None => println!("That was the last move."),
+        },
+        None => println!("Type `solve --step` first."),
+      },
+      Command::Prev => match &mut walkthrough {
+        Some(steps) => {
+          if steps.prev(&mut game) {
+            println!("Back to step {}/{}.", steps.played(), steps.total());
+          } else {
+            println!("That's the start of the solution.");
+          }
+        }
+        None => println!("Type `solve --step` first."),
+      },
+      Command::Automove => {
+        game.check_automove_cards();
+        println!("Tried to automove cards");
+      }
+      Command::Undo => {
+        if !game.undo() {
+          println!("Nothing to undo");
+        }
+      }
+      Command::Pause => {
+        game.pause();
+        println!("Paused. Press enter to keep going.");
+      }
+      Command::Stats => print_stats(&game),
+      Command::Layout(layout) => {
+        printer.layout = layout.unwrap_or(printer.layout.other());
+      }
+      Command::Quit => {
+        println!("bye!");
+        break;
+      }
+      Command::Move(src, dst) => {
+        let res = game.move_card(src, dst);
+        if let Err(ono) = res {
+          println!("You can't do that: {}", ono);
+        }
+      }
     }
+  }
 
-    let Some((src_s, dst_s)) = line.split_once(' ') else {
-      println!("please write a source and dest separated by a space");
-      continue;
-    };
-
-    let Some(src) = read_zone(src_s) else {
-      println!("source zone was written invalid");
-      continue;
-    };
-    let Some(dst) = read_zone(dst_s) else {
-      println!("destination zone was written invalid");
-      continue;
-    };
-
-    let res = board.move_card(src, dst, true);
-    if let Err(ono) = res {
-      println!("You can't do that! {:?}", &ono);
+  if game.in_progress() {
+    finish_game(&game);
+  }
+  if let Some(path) = &history {
+    let saved = path
+      .parent()
+      .map_or(Ok(()), fs::create_dir_all)
+      .map_err(ReadlineError::Io)
+      .and_then(|()| rl.save_history(path));
+    if let Err(ono) = saved {
+      println!("Couldn't save your history: {}", ono);
     }
   }
 }
 
-fn read_zone(s: &str) -> Option<BoardZone> {
-  if let Ok(num) = usize::from_str_radix(s, 10) {
-    return if num > Board::COLUMN_COUNT {
-      None
-    } else {
-      return Some(BoardZone::Column(num));
-    };
+/// Where the lines typed in get saved between games.
+fn history_path() -> Option<PathBuf> {
+  Some(dirs::data_dir()?.join("teletarot").join("history.txt"))
+}
+
+fn prompt(game: &Game) -> String {
+  format!(
+    "[moves {} | undos {} | {} | score {}] > ",
+    game.moves(),
+    game.undos(),
+    format_duration(game.elapsed()),
+    game.score()
+  )
+}
+
+/// Put the game in the record books.
+fn finish_game(game: &Game) {
+  if game.is_solved() && !game.assisted() {
+    println!(
+      "You won in {} moves and {}, scoring {}!",
+      game.moves(),
+      format_duration(game.elapsed()),
+      game.score()
+    );
   }
-  match s {
-    "a" => Some(BoardZone::MinorFoundation),
-    "A" => Some(BoardZone::MajorFoundation),
-    "s" => Some(BoardZone::MinorFoundationStorage),
-    _ => None,
+  if let Err(ono) = Stats::record_to_default_path(game) {
+    println!("Couldn't save your stats: {}", ono);
   }
 }
 
-fn print_board(board: &Board) {
-  let maj = (Card::MAJOR_ARCANA_MIN..=Card::MAJOR_ARCANA_MAX)
-    .map(|idx| {
-      let idx = idx as u8;
-      let has_left = match board.major_foundation_left_max() {
-        None => false,
-        Some(lo) => idx <= lo,
-      };
-      let has_right = match board.major_foundation_right_min() {
-        None => false,
-        Some(hi) => idx >= hi,
-      };
-      if has_left || has_right {
-        format!("{:>2}", idx)
-      } else {
-        "..".to_string()
-      }
-    })
-    .join(" ");
-
-  let minor_maxes = board
-    .virtual_cards_minor_foundation()
-    .map(|v| {
-      let card = v.last();
-      match card {
-        // note i have to call to_string because you have to wire up all
-        // the flags yourself when impling Display/Debug and I can't be assed
-        Some(c) => format!("{:>3}", c.to_string()),
-        None => "...".to_string(),
-      }
-    })
-    .join(" ");
-
-  let columns = (0..Board::COLUMN_COUNT)
-    .map(|col_idx| {
-      let row = board
-        .get_column(col_idx)
-        .iter()
-        .map(|card| format!("{:>3}", card.to_string()))
-        .join(" ");
-      format!("{:>2}. {}", col_idx, row)
-    })
-    .join("\n");
-
-  println!("~{{{}}}~", maj);
-  print!("[{}]", minor_maxes);
-  if let Some(store) = board.minor_foundation_storage() {
-    print!(" -[{:>3}]-", store);
+fn print_stats(game: &Game) {
+  match stats_text(game) {
+    Ok(text) => println!("{}", text),
+    Err(ono) => println!("{}", ono),
   }
-  println!();
+}
 
-  println!("{}", columns);
+/// How you've been doing overall, and on this deal.
+fn stats_text(game: &Game) -> Result<String, String> {
+  let stats = Stats::load_default()
+    .map_err(|ono| format!("Couldn't load your stats: {}", ono))?;
+  Ok(stats.summary(game.generator(), game.seed()))
 }
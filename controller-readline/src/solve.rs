@@ -0,0 +1,72 @@
+use teletarot_model::{game::Game, BoardZone};
+
+pub type Solution = Vec<(BoardZone, BoardZone)>;
+
+/// A solution being stepped through with `next` and `prev`.
+pub struct Walkthrough {
+  moves: Solution,
+  /// How many of the moves have been played.
+  played: usize,
+}
+
+/// Play the whole solution.
+pub fn apply(game: &mut Game, soln: &Solution) {
+  game.mark_assisted();
+  // The solver starts from after the automoves
+  game.check_automove_cards();
+  for &(src, dst) in soln {
+    play(game, src, dst);
+  }
+}
+
+pub fn print_moves(soln: &Solution) {
+  for (src, dst) in soln {
+    println!("{:3} -> {:3}", src.short_name(), dst.short_name());
+  }
+}
+
+/// Play a move from the solver, and the automoves after it.
+fn play(game: &mut Game, src: BoardZone, dst: BoardZone) {
+  let res = game.move_card(src, dst);
+  if let Err(ono) = res {
+    panic!("solver gave bad movement {:?} -> {:?}: {:?}", src, dst, ono);
+  }
+  game.check_automove_cards();
+}
+
+impl Walkthrough {
+  pub fn start(game: &mut Game, soln: Solution) -> Self {
+    game.mark_assisted();
+    game.check_automove_cards();
+    Self {
+      moves: soln,
+      played: 0,
+    }
+  }
+
+  pub fn played(&self) -> usize {
+    self.played
+  }
+
+  pub fn total(&self) -> usize {
+    self.moves.len()
+  }
+
+  /// Play the next move of the solution, if there's any left.
+  pub fn next(&mut self, game: &mut Game) -> Option<(BoardZone, BoardZone)> {
+    let &(src, dst) = self.moves.get(self.played)?;
+    play(game, src, dst);
+    self.played += 1;
+    Some((src, dst))
+  }
+
+  /// Take back the last move of the solution, if there's been one.
+  /// It's not counted as an undo; the game's only marked assisted.
+  pub fn prev(&mut self, game: &mut Game) -> bool {
+    if self.played == 0 || !game.take_back() {
+      return false;
+    }
+    self.played -= 1;
+    true
+  }
+}
@@ -0,0 +1,68 @@
+use std::{
+  io::Write,
+  process::{Command, Output, Stdio},
+};
+
+use teletarot_model::{random::Generator, solver};
+
+const SEED: u64 = 2024;
+
+/// Run the game in batch mode with `moves` piped into it.
+fn run(moves: &str) -> Output {
+  let mut child =
+    Command::new(env!("CARGO_BIN_EXE_teletarot-controller-readline"))
+      .args(["--batch", "--color", "never", "--generator", "smartish"])
+      .args(["--seed", &SEED.to_string()])
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .unwrap();
+  child
+    .stdin
+    .take()
+    .unwrap()
+    .write_all(moves.as_bytes())
+    .unwrap();
+  child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+  String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn solving_the_board_exits_with_0() {
+  let mut board = Generator::Smartish.generate_seeded(SEED);
+  board.check_automove_cards();
+  let soln = solver::try_solve(&board).expect("the deal can be solved");
+  // The solver expects the automoves to happen after every move
+  let mut moves = String::from("z\n");
+  for (src, dst) in &soln {
+    moves += &format!("{} {}\nz\n", src.short_name(), dst.short_name());
+  }
+
+  let output = run(&moves);
+  assert_eq!(output.status.code(), Some(0));
+  let text = stdout(&output);
+  let solved = format!("Solved in {} moves.", soln.len());
+  assert!(text.ends_with(&format!("{}\n", solved)), "{}", text);
+}
+
+#[test]
+fn stopping_early_exits_with_1() {
+  let output = run("# nothing yet\n\nz\n");
+  assert_eq!(output.status.code(), Some(1));
+  let text = stdout(&output);
+  assert!(text.ends_with("Not solved after 0 moves.\n"), "{}", text);
+}
+
+#[test]
+fn a_bad_line_exits_with_2() {
+  let output = run("z\nA 0\n");
+  assert_eq!(output.status.code(), Some(2));
+  let text = stdout(&output);
+  assert!(!text.contains("olved"), "{}", text);
+  let errors = String::from_utf8(output.stderr).unwrap();
+  assert!(errors.starts_with("line 2: \"A 0\": "), "{}", errors);
+}
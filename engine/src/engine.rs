@@ -0,0 +1,324 @@
+use std::time::{Duration, Instant};
+
+use teletarot_model::{
+  player::Move, random::Generator, solver, Board, BoardZone,
+};
+
+use crate::notation::{read_board, write_board, write_moves};
+
+/// The board being played, and the commands that play it.
+pub struct Engine {
+  board: Board,
+  /// Since the last `newgame` or `position`, not counting automoves.
+  moves: u32,
+}
+
+/// How long the solver gets before giving up. No limits means it keeps
+/// going until it's sure.
+#[derive(Debug, Clone, Copy, Default)]
+struct Limits {
+  steps: Option<u64>,
+  time: Option<Duration>,
+}
+
+/// Why a command didn't work. It goes after `error` in the reply,
+/// first a word for programs, then a message for people.
+struct Failure {
+  kind: &'static str,
+  message: String,
+}
+
+impl Engine {
+  pub fn new() -> Self {
+    let mut engine = Self {
+      board: Board::empty(),
+      moves: 0,
+    };
+    engine.set_board(Generator::Smartish.generate(None));
+    engine
+  }
+
+  /// Run one line of the protocol. The reply is always one line,
+  /// or `None` if it's time to quit.
+  pub fn handle(&mut self, line: &str) -> Option<String> {
+    let (command, rest) = match line.trim().split_once(' ') {
+      Some((command, rest)) => (command, rest.trim()),
+      None => (line.trim(), ""),
+    };
+    let args = rest.split_whitespace().collect::<Vec<_>>();
+    let reply = match command {
+      "newgame" => self.newgame(&args),
+      "position" => self.position(rest),
+      "move" => self.play(&args),
+      "legal" => Ok(write_moves(&self.board.legal_moves())),
+      "hint" => self.hint(&args),
+      "solve" => self.solve(&args),
+      "status" => Ok(self.status()),
+      "quit" => return None,
+      _ => Err(usage(format!("don't know the command {:?}", command))),
+    };
+    Some(match reply {
+      Ok(it) if it.is_empty() => "ok".to_string(),
+      Ok(it) => format!("ok {}", it),
+      Err(ono) => format!("error {} {}", ono.kind, ono.message),
+    })
+  }
+
+  /// `newgame [seed] [generator]`, replying with the seed and generator.
+  fn newgame(&mut self, args: &[&str]) -> Result<String, Failure> {
+    let (seed, generator) = match args {
+      [] => (None, None),
+      [seed] => (Some(*seed), None),
+      [seed, generator] => (Some(*seed), Some(*generator)),
+      _ => return Err(usage("newgame [seed] [generator]")),
+    };
+    let seed = match seed {
+      Some(it) => it
+        .parse::<u64>()
+        .map_err(|_| usage(format!("{:?} isn't a seed", it)))?,
+      None => fastrand::u64(..),
+    };
+    let generator = match generator {
+      Some(it) => it.parse::<Generator>().map_err(|()| {
+        let names = Generator::ALL.map(|it| it.name());
+        usage(format!("the generators are {}", names.join(", ")))
+      })?,
+      None => Generator::Smartish,
+    };
+    let auto = self.set_board(generator.generate_seeded(seed));
+    Ok(format!("{} {}{}", seed, generator, auto))
+  }
+
+  /// `position <board>`, setting up the board as written.
+  fn position(&mut self, rest: &str) -> Result<String, Failure> {
+    let board = read_board(rest).map_err(|message| Failure {
+      kind: "position",
+      message,
+    })?;
+    Ok(self.set_board(board).trim_start().to_string())
+  }
+
+  /// `move <src> <dst>`, replying with the automoves after it.
+  fn play(&mut self, args: &[&str]) -> Result<String, Failure> {
+    let [src, dst] = args else {
+      return Err(usage("move <src> <dst>"));
+    };
+    let src = src.parse::<BoardZone>().map_err(usage)?;
+    let dst = dst.parse::<BoardZone>().map_err(usage)?;
+    self
+      .board
+      .move_card(src, dst, true)
+      .map_err(|ono| Failure {
+        kind: ono.kind(),
+        message: ono.to_string(),
+      })?;
+    self.moves += 1;
+    Ok(self.automove().trim_start().to_string())
+  }
+
+  /// `hint [limits]`, replying with the first move of a solution.
+  fn hint(&self, args: &[&str]) -> Result<String, Failure> {
+    let soln = self.find_solution(Limits::parse(args)?)?;
+    match soln.first() {
+      Some(it) => Ok(write_moves(&[*it])),
+      None => Ok(String::new()),
+    }
+  }
+
+  /// `solve [limits]`, replying with how many moves, then the moves.
+  fn solve(&self, args: &[&str]) -> Result<String, Failure> {
+    let soln = self.find_solution(Limits::parse(args)?)?;
+    if soln.is_empty() {
+      return Ok("0".to_string());
+    }
+    Ok(format!("{} {}", soln.len(), write_moves(&soln)))
+  }
+
+  /// `status`, replying with `solved`, `stuck` or `playing`, how many moves
+  /// there have been, and the board.
+  fn status(&self) -> String {
+    let state = if self.board.is_solved() {
+      "solved"
+    } else if self.board.legal_moves().is_empty() {
+      "stuck"
+    } else {
+      "playing"
+    };
+    format!("{} {} {}", state, self.moves, write_board(&self.board))
+  }
+
+  /// An empty solution if the board's already solved.
+  fn find_solution(&self, limits: Limits) -> Result<Vec<Move>, Failure> {
+    if self.board.is_solved() {
+      return Ok(Vec::new());
+    }
+    let started = Instant::now();
+    let mut steps = 0;
+    let mut limited = false;
+    let soln = solver::try_solve_while(&self.board, || {
+      steps += 1;
+      let out_of_steps = limits.steps.is_some_and(|max| steps > max);
+      let out_of_time = limits.time.is_some_and(|max| started.elapsed() > max);
+      limited = out_of_steps || out_of_time;
+      !limited
+    });
+    match soln {
+      Some(it) => Ok(it),
+      None if limited => Err(Failure {
+        kind: "limit",
+        message: "gave up before finding a solution".to_string(),
+      }),
+      None => Err(Failure {
+        kind: "unsolvable",
+        message: "there's no solution".to_string(),
+      }),
+    }
+  }
+
+  /// Start over with a new board, and move what can go to the foundations.
+  fn set_board(&mut self, board: Board) -> String {
+    self.board = board;
+    self.moves = 0;
+    self.automove()
+  }
+
+  /// Move what can go to the foundations, like the solver expects,
+  /// and say what moved as ` auto 3:a 4:A`.
+  fn automove(&mut self) -> String {
+    let moved =
+      std::iter::from_fn(|| self.board.next_automove()).collect::<Vec<_>>();
+    if moved.is_empty() {
+      String::new()
+    } else {
+      format!(" auto {}", write_moves(&moved))
+    }
+  }
+}
+
+impl Limits {
+  /// Like `steps 100000 time 500`, with the time in milliseconds.
+  fn parse(args: &[&str]) -> Result<Self, Failure> {
+    let mut limits = Self::default();
+    for pair in args.chunks(2) {
+      let [name, value] = pair else {
+        return Err(usage("the limits are steps <n> and time <ms>"));
+      };
+      let value = value
+        .parse::<u64>()
+        .map_err(|_| usage(format!("{:?} isn't a number", value)))?;
+      match *name {
+        "steps" => limits.steps = Some(value),
+        "time" => limits.time = Some(Duration::from_millis(value)),
+        _ => return Err(usage("the limits are steps <n> and time <ms>")),
+      }
+    }
+    Ok(limits)
+  }
+}
+
+fn usage(message: impl Into<String>) -> Failure {
+  Failure {
+    kind: "usage",
+    message: message.into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SOLVED: &str = "////////// - 13,13,13,13 10,11";
+
+  /// A smartish deal the solver finds a short solution for.
+  fn dealt() -> Engine {
+    let mut engine = Engine::new();
+    let reply = engine.handle("newgame 45 smartish").unwrap();
+    assert!(reply.starts_with("ok 45 smartish"), "{}", reply);
+    engine
+  }
+
+  fn handle(engine: &mut Engine, line: &str) -> String {
+    engine.handle(line).unwrap()
+  }
+
+  /// The moves after `ok`, checking each one's written like `3:s`.
+  fn moves(reply: &str) -> Vec<(BoardZone, BoardZone)> {
+    let Some(moves) = reply.strip_prefix("ok") else {
+      panic!("{:?} isn't ok", reply);
+    };
+    moves
+      .split_whitespace()
+      .map(|mv| {
+        let (src, dst) = mv.split_once(':').unwrap();
+        (src.parse().unwrap(), dst.parse().unwrap())
+      })
+      .collect()
+  }
+
+  #[test]
+  fn solved_boards_need_no_moves() {
+    let mut engine = Engine::new();
+    assert_eq!(handle(&mut engine, &format!("position {}", SOLVED)), "ok");
+    assert_eq!(handle(&mut engine, "hint"), "ok");
+    assert_eq!(handle(&mut engine, "solve"), "ok 0");
+    assert_eq!(handle(&mut engine, "legal"), "ok");
+    let status = format!("ok solved 0 {}", SOLVED);
+    assert_eq!(handle(&mut engine, "status"), status);
+  }
+
+  #[test]
+  fn bad_positions_leave_the_board_alone() {
+    let mut engine = dealt();
+    let before = handle(&mut engine, "status");
+    for board in [
+      "",
+      "////////// - 13,13,13,13",
+      "///////// - 13,13,13,13 10,11",
+      "////////// 99x 13,13,13,13 10,11",
+      "////////// - 13,13,13 10,11",
+      "////////// - 13,13,13,x 10,11",
+      "////////// - 13,13,13,13 10",
+      "////////// - 12,13,13,13 10,11",
+    ] {
+      let reply = handle(&mut engine, &format!("position {}", board));
+      assert!(reply.starts_with("error position "), "{:?}", reply);
+    }
+    assert_eq!(handle(&mut engine, "status"), before);
+  }
+
+  #[test]
+  fn replies_are_moves_after_ok() {
+    let mut engine = dealt();
+    let legal = moves(&handle(&mut engine, "legal"));
+    assert!(!legal.is_empty());
+
+    let hint = moves(&handle(&mut engine, "hint"));
+    assert_eq!(hint.len(), 1);
+    assert!(legal.contains(&hint[0]));
+
+    let reply = handle(&mut engine, "solve");
+    let (count, soln) = reply
+      .strip_prefix("ok ")
+      .and_then(|it| it.split_once(' '))
+      .unwrap();
+    let soln = moves(&format!("ok {}", soln));
+    assert_eq!(count.parse::<usize>().unwrap(), soln.len());
+    assert_eq!(soln[0], hint[0]);
+
+    for (src, dst) in soln {
+      let line = format!("move {} {}", src.short_name(), dst.short_name());
+      let reply = handle(&mut engine, &line);
+      assert!(reply == "ok" || reply.starts_with("ok auto "), "{}", reply);
+    }
+    assert!(handle(&mut engine, "status").starts_with("ok solved "));
+  }
+
+  #[test]
+  fn the_solver_gives_up_at_its_limits() {
+    let mut engine = dealt();
+    let reply = handle(&mut engine, "solve steps 1");
+    assert!(reply.starts_with("error limit "), "{}", reply);
+    let reply = handle(&mut engine, "hint steps");
+    assert!(reply.starts_with("error usage "), "{}", reply);
+  }
+}
@@ -0,0 +1,55 @@
+//! Teletarot without a face, for other programs to drive over stdin and
+//! stdout, a bit like a UCI chess engine.
+//!
+//! Every line in is a command, and gets exactly one line back: `ok`,
+//! maybe followed by the answer, or `error <kind> <message>`. `kind` is
+//! one word, like `usage`, `limit` or the kind of move error, like
+//! `cannot-stack`. Blank lines aren't commands, so they're skipped without
+//! a reply.
+//!
+//! - `newgame [seed] [generator]` deals a new board, smartish by default,
+//!   and says the seed and generator it used.
+//! - `position <board>` sets up a board written like `status` writes it.
+//! - `move <src> <dst>` moves a card. Zones are column numbers, `s` for the
+//!   storage, `a` for the minor foundation and `A` for the major one.
+//! - `legal` lists every move that can be made, like `3:s 5:1`.
+//! - `hint [limits]` says the first move of a solution.
+//! - `solve [limits]` says how many moves a solution takes, then the moves.
+//!   The limits are `steps <n>` and `time <ms>`; without them it takes as
+//!   long as it takes.
+//! - `status` says whether the board's `solved`, `stuck` or still
+//!   `playing`, how many moves have been made, then the board.
+//! - `quit` stops, and so does the end of stdin.
+//!
+//! After dealing, setting up a board, or moving, the cards that can go to
+//! the foundations go there by themselves, and get listed after `auto`.
+
+use std::io::{self, BufRead, Write};
+
+use engine::Engine;
+
+mod engine;
+mod notation;
+
+fn main() {
+  let mut engine = Engine::new();
+  let mut stdout = io::stdout().lock();
+  for line in io::stdin().lock().lines() {
+    let Ok(line) = line else {
+      break;
+    };
+    if line.trim().is_empty() {
+      continue;
+    }
+    let Some(reply) = engine.handle(&line) else {
+      break;
+    };
+    // Front ends wait for each reply, so it can't sit in a buffer
+    if writeln!(stdout, "{}", reply)
+      .and_then(|()| stdout.flush())
+      .is_err()
+    {
+      break;
+    }
+  }
+}
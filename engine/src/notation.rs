@@ -0,0 +1,162 @@
+//! Writing boards and moves as text, and reading boards back. Zones are
+//! written as `BoardZone::short_name` writes them.
+//!
+//! A board is four fields separated by spaces:
+//!
+//! ```text
+//! 8A,Jc,9c/6w,7s//20A,Kw ... 9c 4,1,1,1 3,20
+//! ```
+//!
+//! 1. The columns, separated by `/`. Each has its cards separated by `,`,
+//!    from the bottom of the pile to the card on top that can be moved.
+//!    An empty column is just nothing.
+//! 2. The card in the storage, or `-`.
+//! 3. The top of each minor foundation as a number, in the order
+//!    pentacles, cups, swords, wands, separated by `,`. `-` if it's empty.
+//! 4. The ends of the major foundation: how high the left side goes and
+//!    how low the right side goes, separated by `,`. `-` if they're empty.
+
+use std::array;
+
+use itertools::Itertools;
+use teletarot_model::{player::Move, Board, Card};
+
+pub fn write_board(board: &Board) -> String {
+  let columns = board
+    .columns()
+    .iter()
+    .map(|col| col.iter().join(","))
+    .join("/");
+  let storage = match board.minor_foundation_storage() {
+    Some(card) => card.to_string(),
+    None => "-".to_string(),
+  };
+  let minor = board
+    .minor_foundation_maxes()
+    .iter()
+    .map(|it| write_number(*it))
+    .join(",");
+  let major = format!(
+    "{},{}",
+    write_number(board.major_foundation_left_max()),
+    write_number(board.major_foundation_right_min())
+  );
+  format!("{} {} {} {}", columns, storage, minor, major)
+}
+
+pub fn read_board(s: &str) -> Result<Board, String> {
+  let fields = s.split_whitespace().collect_vec();
+  let [columns, storage, minor, major] = fields[..] else {
+    return Err(format!("a board has 4 fields, not {}", fields.len()));
+  };
+
+  let columns = columns.split('/').collect_vec();
+  if columns.len() != Board::COLUMN_COUNT {
+    return Err(format!(
+      "a board has {} columns, not {}",
+      Board::COLUMN_COUNT,
+      columns.len()
+    ));
+  }
+  let columns = columns
+    .into_iter()
+    .map(|col| {
+      col
+        .split(',')
+        .filter(|card| !card.is_empty())
+        .map(str::parse::<Card>)
+        .collect::<Result<Vec<_>, _>>()
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut columns = columns.into_iter();
+  let columns = array::from_fn(|_| columns.next().unwrap());
+
+  let storage = match storage {
+    "-" => None,
+    card => Some(card.parse::<Card>()?),
+  };
+
+  let minor = minor
+    .split(',')
+    .map(read_number)
+    .collect::<Result<Vec<_>, _>>()?;
+  let minor: [_; 4] = minor
+    .try_into()
+    .map_err(|_| "the minor foundation has 4 suits".to_string())?;
+
+  let Some((left, right)) = major.split_once(',') else {
+    return Err("the major foundation has a left and a right".to_string());
+  };
+
+  Board::from_parts(
+    columns,
+    storage,
+    minor,
+    read_number(left)?,
+    read_number(right)?,
+  )
+}
+
+fn write_number(num: Option<u8>) -> String {
+  match num {
+    Some(it) => it.to_string(),
+    None => "-".to_string(),
+  }
+}
+
+fn read_number(s: &str) -> Result<Option<u8>, String> {
+  match s {
+    "-" => Ok(None),
+    _ => s
+      .parse()
+      .map(Some)
+      .map_err(|_| format!("{:?} isn't a number", s)),
+  }
+}
+
+/// Like `3:s`.
+pub fn write_move((src, dst): Move) -> String {
+  format!("{}:{}", src.short_name(), dst.short_name())
+}
+
+pub fn write_moves(moves: &[Move]) -> String {
+  moves.iter().map(|mv| write_move(*mv)).join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use teletarot_model::{random::Generator, BoardZone};
+
+  use super::*;
+
+  #[test]
+  fn boards_round_trip() {
+    let mut board = Generator::Shuffled.generate_seeded(7);
+    let written = write_board(&board);
+    assert_eq!(read_board(&written), Ok(board.clone()));
+
+    // Fill in the storage and foundations a bit too
+    board.check_automove_cards();
+    let (src, _) = board
+      .legal_moves()
+      .into_iter()
+      .find(|(_, dst)| *dst == BoardZone::MinorFoundationStorage)
+      .unwrap();
+    board
+      .move_card(src, BoardZone::MinorFoundationStorage, true)
+      .unwrap();
+    let written = write_board(&board);
+    assert!(!written.contains(" - "), "{}", written);
+    assert_eq!(read_board(&written), Ok(board));
+  }
+
+  #[test]
+  fn moves_are_written_with_short_names() {
+    let moves = [
+      (BoardZone::Column(3), BoardZone::MinorFoundationStorage),
+      (BoardZone::Column(10), BoardZone::MajorFoundation),
+    ];
+    assert_eq!(write_moves(&moves), "3:s 10:A");
+    assert_eq!(write_moves(&[]), "");
+  }
+}
@@ -0,0 +1,54 @@
+//! Pit the bundled bots against each other on the same deals.
+//!
+//! `cargo run --release --example tournament -- [deals] [solver steps]`
+//!
+//! Each bot plays seeds 0 up to `deals` (10 by default) from every
+//! generator. The solver bot gives up on a deal after `solver steps`
+//! (a million by default).
+
+use std::{env, process::ExitCode};
+
+use teletarot_model::player::{
+  GreedyPlayer, Player, RandomPlayer, SolverPlayer, Tournament,
+};
+
+fn main() -> ExitCode {
+  let mut args = env::args().skip(1);
+  let Some(deals) = parse_arg(args.next(), 10) else {
+    eprintln!("usage: tournament [deals] [solver steps]");
+    return ExitCode::FAILURE;
+  };
+  let Some(solver_steps) = parse_arg(args.next(), 1_000_000) else {
+    eprintln!("usage: tournament [deals] [solver steps]");
+    return ExitCode::FAILURE;
+  };
+
+  let tournament = Tournament::new((0..deals).collect(), 1000);
+  let mut players: [Box<dyn Player>; 3] = [
+    Box::new(RandomPlayer::new(Some(0))),
+    Box::new(GreedyPlayer::new()),
+    Box::new(SolverPlayer::new(solver_steps)),
+  ];
+  for player in &mut players {
+    let name = player.name().to_string();
+    let standings = tournament.run(player.as_mut(), |generator, seed, it| {
+      let result = if it.won { "won" } else { "lost" };
+      eprintln!(
+        "{} {} seed {}: {} after {} moves",
+        name, generator, seed, result, it.moves
+      );
+    });
+    println!("{}:", name);
+    for (generator, standing) in tournament.generators.iter().zip(standings) {
+      println!("  {:>8}: {}", generator, standing);
+    }
+  }
+  ExitCode::SUCCESS
+}
+
+fn parse_arg(arg: Option<String>, default: u64) -> Option<u64> {
+  match arg {
+    Some(it) => it.parse().ok(),
+    None => Some(default),
+  }
+}
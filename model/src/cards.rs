@@ -1,9 +1,21 @@
-use core::panic;
-use std::fmt::{Debug, Display};
+use alloc::{
+  format,
+  string::{String, ToString},
+};
+use core::{
+  fmt::{Debug, Display},
+  panic,
+  str::FromStr,
+};
 
 use getset::CopyGetters;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enumn::N)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 #[repr(u8)]
 pub enum MinorSuit {
   // today i learned these are not "stars," these are "pentacles".
@@ -27,6 +39,11 @@ impl MinorSuit {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum Suit {
   Minor(MinorSuit),
   MajorArcana,
@@ -90,11 +107,11 @@ impl Card {
 }
 
 impl Display for Card {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     if self.suit.is_minor() {
       f.write_str(&Card::minor_number_string(self.number))?;
     } else {
-      write!(f, "{}", self.number.to_string())?;
+      write!(f, "{}", self.number)?;
     }
     write!(
       f,
@@ -109,9 +126,68 @@ impl Display for Card {
 }
 
 impl Debug for Card {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     f.write_str("Card(")?;
     write!(f, "{}", self)?;
     f.write_str(")")
   }
 }
+
+/// Reads cards written the way they're displayed, like `7c`, `Jp` or `12A`.
+impl FromStr for Card {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let bad = || format!("{:?} isn't a card", s);
+    let suit_char = s.chars().last().ok_or_else(bad)?;
+    let number = &s[..s.len() - suit_char.len_utf8()];
+
+    if suit_char == 'A' {
+      // Only the way it's displayed, so no signs or leading zeros
+      let digits = number.bytes().all(|it| it.is_ascii_digit());
+      if !digits || (number.len() > 1 && number.starts_with('0')) {
+        return Err(bad());
+      }
+      let number = number.parse::<u8>().map_err(|_| bad())?;
+      if number > Card::MAJOR_ARCANA_MAX {
+        return Err(bad());
+      }
+      return Ok(Card::new(Suit::MajorArcana, number));
+    }
+
+    let suit = [
+      MinorSuit::Pentacles,
+      MinorSuit::Cups,
+      MinorSuit::Swords,
+      MinorSuit::Wands,
+    ]
+    .into_iter()
+    .find(|suit| suit.short_char() == suit_char)
+    .ok_or_else(bad)?;
+    let number = (Card::MINOR_ARCANA_MIN..=Card::MINOR_ARCANA_MAX)
+      .find(|num| Card::minor_number_string(*num) == number)
+      .ok_or_else(bad)?;
+    Ok(Card::new(Suit::Minor(suit), number))
+  }
+}
+
+/// Cards are written the same as they're displayed, like `"7c"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+  fn serialize<S: serde::Serializer>(
+    &self,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+  fn deserialize<D: serde::Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let s = <alloc::borrow::Cow<str>>::deserialize(deserializer)?;
+    s.parse().map_err(serde::de::Error::custom)
+  }
+}
@@ -0,0 +1,251 @@
+use std::time::{Duration, Instant};
+
+use getset::CopyGetters;
+
+use crate::{random::Generator, Board, BoardZone, CardMoveError};
+
+/// One deal being played, from the first card moved until it's won or
+/// given up on.
+#[derive(Debug, Clone, CopyGetters)]
+pub struct Game {
+  #[getset(get_copy = "pub")]
+  generator: Generator,
+  #[getset(get_copy = "pub")]
+  seed: u64,
+  board: Board,
+  /// The board before each of the player's moves, for undoing.
+  history: Vec<Board>,
+
+  /// How many moves the player has made. Automoves don't count,
+  /// and undoing a move doesn't take it back off.
+  #[getset(get_copy = "pub")]
+  moves: u32,
+  #[getset(get_copy = "pub")]
+  undos: u32,
+  /// Set if the solver was used to finish this game,
+  /// so it doesn't get counted as a real win.
+  #[getset(get_copy = "pub")]
+  assisted: bool,
+  clock: Stopwatch,
+}
+
+impl Game {
+  /// Points for each card put away in a foundation.
+  pub const SCORE_PER_CARD: i64 = 10;
+  /// Points for winning at all.
+  pub const SCORE_WIN_BONUS: i64 = 500;
+  pub const SCORE_MOVE_COST: i64 = 1;
+  pub const SCORE_UNDO_COST: i64 = 5;
+  /// Every this many seconds on the clock costs a point.
+  pub const SCORE_SECONDS_PER_POINT: u64 = 10;
+
+  /// Deal a new game. If there's no seed, a random one is picked,
+  /// so the deal can always be replayed later.
+  pub fn new(generator: Generator, seed: Option<u64>) -> Self {
+    let seed = seed.unwrap_or_else(|| fastrand::u64(..));
+    Self {
+      generator,
+      seed,
+      board: generator.generate_seeded(seed),
+      history: Vec::new(),
+      moves: 0,
+      undos: 0,
+      assisted: false,
+      clock: Stopwatch::started(),
+    }
+  }
+
+  pub fn board(&self) -> &Board {
+    &self.board
+  }
+
+  /// Move a card on behalf of the player.
+  ///
+  /// This unpauses the clock; nobody plays with their eyes closed.
+  pub fn move_card(
+    &mut self,
+    source_zone: BoardZone,
+    dest_zone: BoardZone,
+  ) -> Result<(), CardMoveError> {
+    let before = self.board.clone();
+    self.board.move_card(source_zone, dest_zone, true)?;
+    self.history.push(before);
+    self.moves += 1;
+    self.clock.resume();
+    Ok(())
+  }
+
+  /// Move whatever cards can go to the foundations.
+  ///
+  /// These moves get undone along with the player's move before them.
+  pub fn check_automove_cards(&mut self) {
+    self.board.check_automove_cards();
+  }
+
+  /// Move just one card to the foundations, if any can go,
+  /// for showing automoves one at a time.
+  pub fn next_automove(&mut self) -> Option<(BoardZone, BoardZone)> {
+    self.board.next_automove()
+  }
+
+  /// Take back the last move, returning if there was one to take back.
+  pub fn undo(&mut self) -> bool {
+    if !self.take_back() {
+      return false;
+    }
+    self.undos += 1;
+    true
+  }
+
+  /// Like [`Game::undo`], but it isn't counted as an undo or scored,
+  /// for stepping back through a solution the player was shown.
+  pub fn take_back(&mut self) -> bool {
+    let Some(before) = self.history.pop() else {
+      return false;
+    };
+    self.board = before;
+    self.clock.resume();
+    true
+  }
+
+  pub fn can_undo(&self) -> bool {
+    !self.history.is_empty()
+  }
+
+  pub fn mark_assisted(&mut self) {
+    self.assisted = true;
+  }
+
+  pub fn pause(&mut self) {
+    self.clock.pause();
+  }
+
+  pub fn resume(&mut self) {
+    self.clock.resume();
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.clock.is_paused()
+  }
+
+  /// How long the game has been played for, not counting time paused.
+  pub fn elapsed(&self) -> Duration {
+    self.clock.elapsed()
+  }
+
+  /// The score so far.
+  ///
+  /// Each card in a foundation is worth [`Game::SCORE_PER_CARD`], plus
+  /// [`Game::SCORE_WIN_BONUS`] for winning; moves, undos and time on the
+  /// clock take points off. It never goes below zero.
+  pub fn score(&self) -> i64 {
+    let cards = self.board.foundation_card_count() as i64;
+    let bonus = if self.is_solved() {
+      Self::SCORE_WIN_BONUS
+    } else {
+      0
+    };
+    let time_cost =
+      (self.elapsed().as_secs() / Self::SCORE_SECONDS_PER_POINT) as i64;
+    let score = cards * Self::SCORE_PER_CARD + bonus
+      - self.moves as i64 * Self::SCORE_MOVE_COST
+      - self.undos as i64 * Self::SCORE_UNDO_COST
+      - time_cost;
+    score.max(0)
+  }
+
+  pub fn is_solved(&self) -> bool {
+    self.board.is_solved()
+  }
+
+  /// Whether quitting now would abandon anything.
+  pub fn in_progress(&self) -> bool {
+    self.moves > 0 && !self.is_solved()
+  }
+
+  #[cfg(feature = "serde")]
+  pub fn summary(&self) -> Summary<'_> {
+    Summary {
+      generator: self.generator,
+      seed: self.seed,
+      moves: self.moves,
+      undos: self.undos,
+      score: self.score(),
+      solved: self.is_solved(),
+      board: &self.board,
+    }
+  }
+
+  /// How this game would go in the record books if it ended right now.
+  pub fn outcome(&self) -> GameOutcome {
+    if self.is_solved() && !self.assisted {
+      GameOutcome::Won {
+        time: self.elapsed(),
+        moves: self.moves,
+      }
+    } else {
+      GameOutcome::Abandoned
+    }
+  }
+}
+
+/// How a game's going, for sending to other programs. The clock and the
+/// undo history stay behind.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Summary<'a> {
+  pub generator: Generator,
+  pub seed: u64,
+  pub moves: u32,
+  pub undos: u32,
+  pub score: i64,
+  pub solved: bool,
+  pub board: &'a Board,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+  Won { time: Duration, moves: u32 },
+  Abandoned,
+}
+
+/// A clock that can be paused.
+#[derive(Debug, Clone)]
+struct Stopwatch {
+  /// Time from before the last pause.
+  banked: Duration,
+  /// When it was last unpaused, if it's running.
+  running_since: Option<Instant>,
+}
+
+impl Stopwatch {
+  fn started() -> Self {
+    Self {
+      banked: Duration::ZERO,
+      running_since: Some(Instant::now()),
+    }
+  }
+
+  fn pause(&mut self) {
+    if let Some(since) = self.running_since.take() {
+      self.banked += since.elapsed();
+    }
+  }
+
+  fn resume(&mut self) {
+    if self.running_since.is_none() {
+      self.running_since = Some(Instant::now());
+    }
+  }
+
+  fn is_paused(&self) -> bool {
+    self.running_since.is_none()
+  }
+
+  fn elapsed(&self) -> Duration {
+    match self.running_since {
+      Some(since) => self.banked + since.elapsed(),
+      None => self.banked,
+    }
+  }
+}
@@ -1,11 +1,26 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 mod cards;
 
+#[cfg(feature = "std")]
+pub mod game;
+#[cfg(feature = "std")]
+pub mod player;
 pub mod random;
+#[cfg(feature = "std")]
+pub mod stats;
 
 #[cfg(feature = "solver")]
 pub mod solver;
 
-use std::array;
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec::Vec,
+};
+use core::{array, fmt::Display, str::FromStr};
 
 pub use cards::*;
 
@@ -15,6 +30,11 @@ use itertools::Itertools;
 #[derive(
   Debug, Clone, PartialEq, Eq, Hash, Getters, CopyGetters, MutGetters,
 )]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(try_from = "RawBoard")
+)]
 pub struct Board {
   #[getset(get = "pub")]
   columns: [Column; Board::COLUMN_COUNT],
@@ -69,6 +89,74 @@ impl Board {
     }
   }
 
+  /// Put a board together from what's in each zone, checking that it has
+  /// every card exactly once.
+  pub fn from_parts(
+    columns: [Vec<Card>; Board::COLUMN_COUNT],
+    minor_foundation_storage: Option<Card>,
+    minor_foundation_maxes: [Option<u8>; 4],
+    major_foundation_left_max: Option<u8>,
+    major_foundation_right_min: Option<u8>,
+  ) -> Result<Board, String> {
+    let board = Board {
+      columns: columns.map(Column::new),
+      minor_foundation_storage,
+      minor_foundation_maxes,
+      major_foundation_left_max,
+      major_foundation_right_min,
+    };
+    board.check_integrity()?;
+    Ok(board)
+  }
+
+  /// Make sure the foundations make sense, and that every card's
+  /// somewhere on the board exactly once.
+  pub fn check_integrity(&self) -> Result<(), String> {
+    let minor_range = Card::MINOR_ARCANA_MIN..=Card::MINOR_ARCANA_MAX;
+    let major_range = Card::MAJOR_ARCANA_MIN..=Card::MAJOR_ARCANA_MAX;
+    for max in self.minor_foundation_maxes.iter().flatten() {
+      if !minor_range.contains(max) {
+        return Err(format!("a minor foundation can't go up to {}", max));
+      }
+    }
+    let (left, right) = (
+      self.major_foundation_left_max,
+      self.major_foundation_right_min,
+    );
+    for end in [left, right].into_iter().flatten() {
+      if !major_range.contains(&end) {
+        return Err(format!("the major foundation can't have {} in it", end));
+      }
+    }
+    if let (Some(l), Some(r)) = (left, right) {
+      if l >= r {
+        return Err(format!(
+          "the major foundation's left side ({}) has to be under its right \
+           side ({})",
+          l, r
+        ));
+      }
+    }
+
+    let on_board = self
+      .columns
+      .iter()
+      .flat_map(|col| col.iter().cloned())
+      .chain(self.minor_foundation_storage.clone())
+      .chain(self.virtual_cards_minor_foundation().into_iter().flatten())
+      .chain(self.virtual_cards_major_foundation_left())
+      .chain(self.virtual_cards_major_foundation_right())
+      .collect_vec();
+    for card in Board::all_cards() {
+      match on_board.iter().filter(|it| **it == card).count() {
+        1 => {}
+        0 => return Err(format!("{} is missing", card)),
+        n => return Err(format!("{} is there {} times", card, n)),
+      }
+    }
+    Ok(())
+  }
+
   pub fn move_card(
     &mut self,
     source_zone: BoardZone,
@@ -76,11 +164,12 @@ impl Board {
     cascade_column_stacks: bool,
   ) -> Result<(), CardMoveError> {
     if source_zone == dest_zone {
-      return Err(CardMoveError::NoopMovement);
+      return Err(CardMoveError::NoopMovement { zone: source_zone });
     }
     if source_zone.is_write_only() {
-      return Err(CardMoveError::WriteOnlySource);
+      return Err(CardMoveError::WriteOnlySource { zone: source_zone });
     }
+    let empty = CardMoveError::EmptySource { zone: source_zone };
 
     if let (
       true,
@@ -90,23 +179,20 @@ impl Board {
     {
       let src_col = self.get_column(*src_col_idx);
 
-      let src_head = src_col.last().ok_or(CardMoveError::EmptySource)?;
+      let src_head = src_col.last().ok_or(empty)?;
       let dst_head = self.get_column(*dst_col_idx).last();
-      let stack_ok = match dst_head {
-        Some(it) => src_head.can_stack(it),
-        None => true,
-      };
-      if !stack_ok {
-        return Err(CardMoveError::CannotStack);
+      if let Some(dst_head) = dst_head {
+        if !src_head.can_stack(dst_head) {
+          return Err(CardMoveError::CannotStack {
+            card: src_head.clone(),
+            src: source_zone,
+            dest: dest_zone,
+            onto: Some(dst_head.clone()),
+          });
+        }
       }
 
-      let source_take_count = 1
-        + src_col
-          .iter()
-          .rev()
-          .tuple_windows()
-          .take_while(|(prev, here)| prev.can_stack(here))
-          .count();
+      let source_take_count = src_col.movable_run_len();
 
       let src_col_mut = self.get_column_mut(*src_col_idx);
       let sc_len = src_col_mut.len();
@@ -128,35 +214,48 @@ impl Board {
         )
       }
     };
-    let source_card = source_card.ok_or(CardMoveError::EmptySource)?.clone();
+    let source_card = source_card.ok_or(empty)?.clone();
+    let cannot_stack = |onto: Option<&Card>| CardMoveError::CannotStack {
+      card: source_card.clone(),
+      src: source_zone,
+      dest: dest_zone,
+      onto: onto.cloned(),
+    };
+    let wrong_target = CardMoveError::WrongTargetZone {
+      card: source_card.clone(),
+      src: source_zone,
+      dest: dest_zone,
+    };
+    let blocked = |stored: &Card| CardMoveError::BlockedByFullMinorStorage {
+      dest: dest_zone,
+      stored: stored.clone(),
+    };
 
     match &dest_zone {
       BoardZone::Column(idx) => {
         let dst_col = self.get_column_mut(*idx);
-        let stack_ok = match dst_col.last() {
-          None => true,
-          Some(here) => source_card.can_stack(here),
-        };
-        if !stack_ok {
-          return Err(CardMoveError::CannotStack);
+        if let Some(here) = dst_col.last() {
+          if !source_card.can_stack(here) {
+            return Err(cannot_stack(Some(here)));
+          }
         }
 
         // will remove from the source in just a second!
         dst_col.push(source_card);
       }
       BoardZone::MinorFoundationStorage => {
-        if self.minor_foundation_storage.is_some() {
-          return Err(CardMoveError::BlockedByFullMinorStorage);
+        if let Some(stored) = &self.minor_foundation_storage {
+          return Err(blocked(stored));
         }
         self.minor_foundation_storage = Some(source_card);
       }
       BoardZone::MinorFoundation => {
-        if self.minor_foundation_storage.is_some() {
-          return Err(CardMoveError::BlockedByFullMinorStorage);
+        if let Some(stored) = &self.minor_foundation_storage {
+          return Err(blocked(stored));
         }
         let suit = match source_card.suit() {
           Suit::Minor(it) => it,
-          Suit::MajorArcana => return Err(CardMoveError::WrongTargetZone),
+          Suit::MajorArcana => return Err(wrong_target),
         };
 
         let stack_height = &mut self.minor_foundation_maxes[suit as usize];
@@ -167,12 +266,14 @@ impl Board {
         if stack_ok {
           *stack_height = Some(stack_height.unwrap_or_default() + 1);
         } else {
-          return Err(CardMoveError::CannotStack);
+          let onto =
+            stack_height.map(|height| Card::new(Suit::Minor(suit), height));
+          return Err(cannot_stack(onto.as_ref()));
         }
       }
       BoardZone::MajorFoundation => {
         if source_card.suit().is_minor() {
-          return Err(CardMoveError::WrongTargetZone);
+          return Err(wrong_target);
         }
 
         let left_ok = match self.major_foundation_left_max {
@@ -184,7 +285,7 @@ impl Board {
           Some(r) => source_card.number() + 1 == r,
         };
         if !left_ok && !right_ok {
-          return Err(CardMoveError::CannotStack);
+          return Err(cannot_stack(None));
         }
         if left_ok {
           self.major_foundation_left_max = Some(source_card.number());
@@ -213,25 +314,65 @@ impl Board {
 
     Ok(())
   }
-  pub fn check_automove_cards(&mut self) {
-    'columns: loop {
-      let moved_any = (0..Board::COLUMN_COUNT).any(|col_idx| {
-        let src_zone = BoardZone::Column(col_idx);
-        let moved_any =
-          [BoardZone::MinorFoundation, BoardZone::MajorFoundation]
-            .iter()
-            .any(|dst| {
-              let res = self.move_card(src_zone.clone(), dst.clone(), true);
-              res.is_ok()
-            });
-        moved_any
-      });
-      if !moved_any {
-        break 'columns;
-      };
+
+  /// Every zone the card, or run of cards, on top of `src` could move to.
+  pub fn legal_destinations(&self, src: BoardZone) -> Vec<BoardZone> {
+    BoardZone::all()
+      .filter(|dst| self.clone().move_card(src, *dst, true).is_ok())
+      .collect()
+  }
+
+  /// Every move that can be made, as source and destination.
+  pub fn legal_moves(&self) -> Vec<(BoardZone, BoardZone)> {
+    BoardZone::all()
+      .filter(|src| !src.is_write_only())
+      .flat_map(|src| {
+        self
+          .legal_destinations(src)
+          .into_iter()
+          .map(move |dst| (src, dst))
+      })
+      .collect()
+  }
+
+  /// If the card is the next one a foundation's waiting for,
+  /// whether or not anything's in the way of it getting there.
+  pub fn is_next_for_foundation(&self, card: &Card) -> bool {
+    match card.suit() {
+      Suit::Minor(suit) => {
+        let foundation_max = self.minor_foundation_maxes[suit as usize];
+        card.number() == foundation_max.unwrap_or_default() + 1
+      }
+      Suit::MajorArcana => {
+        let next_hi_min = match self.major_foundation_left_max {
+          Some(it) => card.number() == it + 1,
+          None => card.number() == Card::MAJOR_ARCANA_MIN,
+        };
+        let next_lo_max = match self.major_foundation_right_min {
+          Some(it) => card.number() + 1 == it,
+          None => card.number() == Card::MAJOR_ARCANA_MAX,
+        };
+        next_hi_min || next_lo_max
+      }
     }
   }
 
+  pub fn check_automove_cards(&mut self) {
+    while self.next_automove().is_some() {}
+  }
+
+  /// Move one card that can go to a foundation, if there is one,
+  /// and return where it went.
+  pub fn next_automove(&mut self) -> Option<(BoardZone, BoardZone)> {
+    (0..Board::COLUMN_COUNT).find_map(|col_idx| {
+      let src_zone = BoardZone::Column(col_idx);
+      [BoardZone::MinorFoundation, BoardZone::MajorFoundation]
+        .into_iter()
+        .find(|dst| self.move_card(src_zone, *dst, true).is_ok())
+        .map(|dst| (src_zone, dst))
+    })
+  }
+
   pub fn get_column(&self, idx: usize) -> &Column {
     &self.columns[idx]
   }
@@ -305,6 +446,25 @@ impl Board {
     minors && majors
   }
 
+  /// How many cards have been put away in the foundations,
+  /// including the minor aces that start out there.
+  pub fn foundation_card_count(&self) -> usize {
+    let minors = self
+      .minor_foundation_maxes
+      .iter()
+      .map(|top| top.unwrap_or_default() as usize)
+      .sum::<usize>();
+    let left = match self.major_foundation_left_max {
+      Some(l) => (l - Card::MAJOR_ARCANA_MIN + 1) as usize,
+      None => 0,
+    };
+    let right = match self.major_foundation_right_min {
+      Some(r) => (Card::MAJOR_ARCANA_MAX - r + 1) as usize,
+      None => 0,
+    };
+    minors + left + right
+  }
+
   /// Return an iterator of all the cards.
   pub fn all_cards() -> impl Iterator<Item = Card> {
     let minors =
@@ -319,7 +479,38 @@ impl Board {
   }
 }
 
+/// What a board deserializes from, before it's been checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawBoard {
+  columns: [Vec<Card>; Board::COLUMN_COUNT],
+  minor_foundation_storage: Option<Card>,
+  minor_foundation_maxes: [Option<u8>; 4],
+  major_foundation_left_max: Option<u8>,
+  major_foundation_right_min: Option<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawBoard> for Board {
+  type Error = String;
+
+  fn try_from(raw: RawBoard) -> Result<Self, Self::Error> {
+    Board::from_parts(
+      raw.columns,
+      raw.minor_foundation_storage,
+      raw.minor_foundation_maxes,
+      raw.major_foundation_left_max,
+      raw.major_foundation_right_min,
+    )
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Getters, CopyGetters)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(transparent)
+)]
 pub struct Column {
   /// These go top-to-bottom, so the only accessible card is the last one.
   cards: Vec<Card>,
@@ -335,13 +526,13 @@ impl Column {
   }
 }
 
-impl std::ops::DerefMut for Column {
+impl core::ops::DerefMut for Column {
   fn deref_mut(&mut self) -> &mut Self::Target {
     &mut self.cards
   }
 }
 
-impl std::ops::Deref for Column {
+impl core::ops::Deref for Column {
   type Target = Vec<Card>;
 
   fn deref(&self) -> &Self::Target {
@@ -349,7 +540,22 @@ impl std::ops::Deref for Column {
   }
 }
 
-impl Column {}
+impl Column {
+  /// How many cards on top of this column stack onto each other,
+  /// and so get moved together.
+  pub fn movable_run_len(&self) -> usize {
+    if self.cards.is_empty() {
+      return 0;
+    }
+    1 + self
+      .cards
+      .iter()
+      .rev()
+      .tuple_windows()
+      .take_while(|(prev, here)| prev.can_stack(here))
+      .count()
+  }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum BoardZone {
@@ -361,6 +567,15 @@ pub enum BoardZone {
 }
 
 impl BoardZone {
+  /// Every zone on the board, columns first.
+  pub fn all() -> impl Iterator<Item = BoardZone> {
+    (0..Board::COLUMN_COUNT).map(BoardZone::Column).chain([
+      BoardZone::MinorFoundationStorage,
+      BoardZone::MinorFoundation,
+      BoardZone::MajorFoundation,
+    ])
+  }
+
   /// Whether this zone can only have cards placed in it, and not removed from.
   pub fn is_write_only(&self) -> bool {
     match self {
@@ -371,9 +586,14 @@ impl BoardZone {
     }
   }
 
+  /// How the zone's written for typing in and sending between programs:
+  /// columns are their index, then there's `s` for the storage, `a` for
+  /// the minor foundation and `A` for the major foundation.
+  ///
+  /// [`BoardZone::from_str`] reads it back.
   pub fn short_name(&self) -> String {
     match self {
-      BoardZone::Column(c) => format!("c{}", c),
+      BoardZone::Column(c) => c.to_string(),
       BoardZone::MinorFoundationStorage => "s".to_string(),
       BoardZone::MinorFoundation => "a".to_string(),
       BoardZone::MajorFoundation => "A".to_string(),
@@ -381,12 +601,162 @@ impl BoardZone {
   }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl FromStr for BoardZone {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    BoardZone::all()
+      .find(|zone| zone.short_name() == s)
+      .ok_or_else(|| format!("{:?} isn't a zone", s))
+  }
+}
+
+/// Zones are written as their [`BoardZone::short_name`], like `"3"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BoardZone {
+  fn serialize<S: serde::Serializer>(
+    &self,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.short_name())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BoardZone {
+  fn deserialize<D: serde::Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let s = <alloc::borrow::Cow<str>>::deserialize(deserializer)?;
+    s.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+impl Display for BoardZone {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      BoardZone::Column(c) => write!(f, "column {}", c),
+      BoardZone::MinorFoundationStorage => f.write_str("the storage"),
+      BoardZone::MinorFoundation => f.write_str("the minor foundation"),
+      BoardZone::MajorFoundation => f.write_str("the major foundation"),
+    }
+  }
+}
+
+/// Why a card couldn't be moved.
+///
+/// With serde it's an object with the [`CardMoveError::kind`] under
+/// `kind`, next to the fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "kind", rename_all = "kebab-case")
+)]
 pub enum CardMoveError {
-  NoopMovement,
-  EmptySource,
-  WriteOnlySource,
-  CannotStack,
-  WrongTargetZone,
-  BlockedByFullMinorStorage,
+  /// The source and destination are the same.
+  NoopMovement {
+    zone: BoardZone,
+  },
+  EmptySource {
+    zone: BoardZone,
+  },
+  /// Cards can't come back off the foundations.
+  WriteOnlySource {
+    zone: BoardZone,
+  },
+  /// The card doesn't follow on from the one it's going on to.
+  /// `onto` is `None` if there's nothing there to follow on from, like
+  /// a major arcana card that's neither end of the major foundation.
+  CannotStack {
+    card: Card,
+    src: BoardZone,
+    dest: BoardZone,
+    onto: Option<Card>,
+  },
+  /// Like a major arcana card going to the minor foundation.
+  WrongTargetZone {
+    card: Card,
+    src: BoardZone,
+    dest: BoardZone,
+  },
+  /// The storage, and the minor foundation under it, are blocked
+  /// until `stored` moves out.
+  BlockedByFullMinorStorage {
+    dest: BoardZone,
+    stored: Card,
+  },
+}
+
+impl CardMoveError {
+  /// A name for the kind of error, for programs to tell them apart.
+  /// It's the same as the serde tag, like `cannot-stack`.
+  pub fn kind(&self) -> &'static str {
+    match self {
+      CardMoveError::NoopMovement { .. } => "noop-movement",
+      CardMoveError::EmptySource { .. } => "empty-source",
+      CardMoveError::WriteOnlySource { .. } => "write-only-source",
+      CardMoveError::CannotStack { .. } => "cannot-stack",
+      CardMoveError::WrongTargetZone { .. } => "wrong-target-zone",
+      CardMoveError::BlockedByFullMinorStorage { .. } => {
+        "blocked-by-full-minor-storage"
+      }
+    }
+  }
+
+  /// The error with its message alongside, for sending to other programs.
+  #[cfg(feature = "serde")]
+  pub fn explained(&self) -> ExplainedMoveError<'_> {
+    ExplainedMoveError {
+      error: self,
+      message: self.to_string(),
+    }
+  }
 }
+
+/// A [`CardMoveError`] as serde writes it, with a `message` for people
+/// next to its `kind` and fields.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExplainedMoveError<'a> {
+  #[serde(flatten)]
+  pub error: &'a CardMoveError,
+  pub message: String,
+}
+
+impl Display for CardMoveError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      CardMoveError::NoopMovement { zone } => {
+        write!(f, "that's already in {}", zone)
+      }
+      CardMoveError::EmptySource { zone } => write!(f, "{} is empty", zone),
+      CardMoveError::WriteOnlySource { zone } => {
+        write!(f, "cards can't come back off {}", zone)
+      }
+      CardMoveError::CannotStack {
+        card,
+        src,
+        onto: Some(onto),
+        dest: BoardZone::Column(_),
+      } => write!(f, "{} from {} can't stack on {}", card, src, onto),
+      CardMoveError::CannotStack {
+        card, src, dest, ..
+      } => {
+        write!(f, "{} from {} can't go on {} yet", card, src, dest)
+      }
+      CardMoveError::WrongTargetZone { card, src, dest } => {
+        write!(f, "{} from {} doesn't go on {}", card, src, dest)
+      }
+      CardMoveError::BlockedByFullMinorStorage {
+        dest: BoardZone::MinorFoundationStorage,
+        stored,
+      } => write!(f, "the storage is occupied by {}", stored),
+      CardMoveError::BlockedByFullMinorStorage { dest, stored } => {
+        write!(f, "{} is blocked until {} leaves the storage", dest, stored)
+      }
+    }
+  }
+}
+
+impl core::error::Error for CardMoveError {}
@@ -0,0 +1,297 @@
+//! Bots that play the game by themselves, and a tournament to see
+//! how well they do.
+//!
+//! A bot only ever gets shown the board, after the automoves are done,
+//! and picks a move from it or gives up.
+
+use std::{collections::HashSet, fmt::Display};
+
+use getset::CopyGetters;
+
+use crate::{random::Generator, Board, BoardZone};
+
+/// A source and destination to move a card between.
+pub type Move = (BoardZone, BoardZone);
+
+pub trait Player {
+  fn name(&self) -> &str;
+
+  /// Pick the next move to play on the board, or `None` to give up.
+  fn next_move(&mut self, board: &Board) -> Option<Move>;
+
+  /// Forget anything from the last game, before a new one's dealt.
+  fn new_game(&mut self) {}
+}
+
+/// Plays any legal move at all.
+pub struct RandomPlayer {
+  rng: fastrand::Rng,
+}
+
+impl RandomPlayer {
+  pub fn new(seed: Option<u64>) -> Self {
+    let rng = match seed {
+      Some(seed) => fastrand::Rng::with_seed(seed),
+      None => fastrand::Rng::new(),
+    };
+    Self { rng }
+  }
+}
+
+impl Player for RandomPlayer {
+  fn name(&self) -> &str {
+    "random"
+  }
+
+  fn next_move(&mut self, board: &Board) -> Option<Move> {
+    let moves = board.legal_moves();
+    if moves.is_empty() {
+      return None;
+    }
+    Some(moves[self.rng.usize(..moves.len())])
+  }
+}
+
+/// Plays whichever move gets the most cards into the foundations,
+/// and after that whichever leaves the most cards ready to go there.
+///
+/// It never goes back to a board it's already seen, so it gives up
+/// instead of going round in circles.
+#[derive(Default)]
+pub struct GreedyPlayer {
+  seen: HashSet<Board>,
+}
+
+impl GreedyPlayer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Bigger is better.
+  fn rate(board: &Board) -> (usize, usize, usize, bool) {
+    let playable = board
+      .columns()
+      .iter()
+      .filter_map(|col| col.last())
+      .chain(board.minor_foundation_storage())
+      .filter(|card| board.is_next_for_foundation(card))
+      .count();
+    let empty = board.columns().iter().filter(|col| col.is_empty()).count();
+    (
+      board.foundation_card_count(),
+      playable,
+      empty,
+      board.minor_foundation_storage().is_none(),
+    )
+  }
+}
+
+impl Player for GreedyPlayer {
+  fn name(&self) -> &str {
+    "greedy"
+  }
+
+  fn next_move(&mut self, board: &Board) -> Option<Move> {
+    self.seen.insert(board.clone());
+    let (mv, after) = board
+      .legal_moves()
+      .into_iter()
+      .filter_map(|(src, dst)| {
+        let mut after = board.clone();
+        after.move_card(src, dst, true).ok()?;
+        after.check_automove_cards();
+        (!self.seen.contains(&after)).then_some(((src, dst), after))
+      })
+      // max_by_key picks the last of the best, and i want the first
+      .rev()
+      .max_by_key(|(_, after)| Self::rate(after))?;
+    self.seen.insert(after);
+    Some(mv)
+  }
+
+  fn new_game(&mut self) {
+    self.seen.clear();
+  }
+}
+
+/// Asks the solver how to win and plays that.
+///
+/// The solver can take forever on a board it can't solve, so it gets
+/// `max_steps` steps to find a solution before the bot gives up.
+#[cfg(feature = "solver")]
+pub struct SolverPlayer {
+  max_steps: u64,
+  /// The solution, backwards, so the next move is on the end.
+  plan: Vec<Move>,
+  /// What the board should look like if the plan's being followed.
+  expecting: Option<Board>,
+}
+
+#[cfg(feature = "solver")]
+impl SolverPlayer {
+  pub fn new(max_steps: u64) -> Self {
+    Self {
+      max_steps,
+      plan: Vec::new(),
+      expecting: None,
+    }
+  }
+}
+
+#[cfg(feature = "solver")]
+impl Player for SolverPlayer {
+  fn name(&self) -> &str {
+    "solver"
+  }
+
+  fn next_move(&mut self, board: &Board) -> Option<Move> {
+    if self.expecting.as_ref() != Some(board) {
+      let mut steps = 0;
+      let soln = crate::solver::try_solve_while(board, || {
+        steps += 1;
+        steps <= self.max_steps
+      });
+      self.plan = soln?;
+      self.plan.reverse();
+    }
+
+    let (src, dst) = self.plan.pop()?;
+    let mut after = board.clone();
+    after.move_card(src, dst, true).ok()?;
+    after.check_automove_cards();
+    self.expecting = Some(after);
+    Some((src, dst))
+  }
+
+  fn new_game(&mut self) {
+    self.plan.clear();
+    self.expecting = None;
+  }
+}
+
+/// How one game went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+  pub won: bool,
+  /// Not counting automoves.
+  pub moves: u32,
+}
+
+/// Let the player play the board until they win, give up, make a move
+/// that doesn't work, or run out of moves.
+pub fn play_game(
+  player: &mut dyn Player,
+  mut board: Board,
+  max_moves: u32,
+) -> Outcome {
+  player.new_game();
+  board.check_automove_cards();
+  let mut moves = 0;
+  while !board.is_solved() && moves < max_moves {
+    let Some((src, dst)) = player.next_move(&board) else {
+      break;
+    };
+    if board.move_card(src, dst, true).is_err() {
+      break;
+    }
+    moves += 1;
+    board.check_automove_cards();
+  }
+  Outcome {
+    won: board.is_solved(),
+    moves,
+  }
+}
+
+/// Every player gets the same deals: each seed from each generator.
+pub struct Tournament {
+  pub generators: Vec<Generator>,
+  pub seeds: Vec<u64>,
+  /// Games still going after this many moves are lost.
+  pub max_moves: u32,
+}
+
+/// How one player did with one generator's deals.
+#[derive(Debug, Clone, Default, PartialEq, Eq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Standing {
+  played: u32,
+  won: u32,
+  /// Over the games that got won.
+  total_moves: u64,
+}
+
+impl Tournament {
+  pub fn new(seeds: Vec<u64>, max_moves: u32) -> Self {
+    Self {
+      generators: Generator::ALL.to_vec(),
+      seeds,
+      max_moves,
+    }
+  }
+
+  /// Play every deal with the player, and call `on_game` after each one.
+  /// Standings come back in the same order as `generators`.
+  pub fn run(
+    &self,
+    player: &mut dyn Player,
+    mut on_game: impl FnMut(Generator, u64, Outcome),
+  ) -> Vec<Standing> {
+    self
+      .generators
+      .iter()
+      .map(|&generator| {
+        let mut standing = Standing::default();
+        for &seed in &self.seeds {
+          let board = generator.generate_seeded(seed);
+          let outcome = play_game(player, board, self.max_moves);
+          standing.record(outcome);
+          on_game(generator, seed, outcome);
+        }
+        standing
+      })
+      .collect()
+  }
+}
+
+impl Standing {
+  fn record(&mut self, outcome: Outcome) {
+    self.played += 1;
+    if outcome.won {
+      self.won += 1;
+      self.total_moves += outcome.moves as u64;
+    }
+  }
+
+  /// From 0 to 1.
+  pub fn win_rate(&self) -> f64 {
+    if self.played == 0 {
+      return 0.0;
+    }
+    self.won as f64 / self.played as f64
+  }
+
+  /// How many moves it took to win, on average.
+  pub fn average_moves(&self) -> Option<f64> {
+    if self.won == 0 {
+      return None;
+    }
+    Some(self.total_moves as f64 / self.won as f64)
+  }
+}
+
+impl Display for Standing {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "won {}/{} ({:.0}%)",
+      self.won,
+      self.played,
+      self.win_rate() * 100.0
+    )?;
+    match self.average_moves() {
+      Some(it) => write!(f, ", {:.1} moves on average", it),
+      None => f.write_str(", moves on average -"),
+    }
+  }
+}
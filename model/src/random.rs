@@ -1,7 +1,67 @@
 mod shuffled;
 mod smartish;
 
-/// Generate a completely random board. Chances are, it's unsolvable.
+use core::{fmt::Display, str::FromStr};
+
+use crate::Board;
+
+#[cfg(feature = "std")]
 pub use shuffled::shuffled_random;
-/// Generate a board that's (probably) solvable by (mostly) "un-playing" a solution.
+/// Generate a completely random board. Chances are, it's unsolvable.
+pub use shuffled::shuffled_random_seeded;
+#[cfg(feature = "std")]
 pub use smartish::smartish_random;
+/// Generate a board that's (probably) solvable by (mostly) "un-playing" a solution.
+pub use smartish::smartish_random_seeded;
+
+/// One of the ways of dealing a board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
+pub enum Generator {
+  Shuffled,
+  Smartish,
+}
+
+impl Generator {
+  pub const ALL: [Generator; 2] = [Generator::Shuffled, Generator::Smartish];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Generator::Shuffled => "shuffled",
+      Generator::Smartish => "smartish",
+    }
+  }
+
+  /// Deal a board. Without a seed a random one gets picked.
+  #[cfg(feature = "std")]
+  pub fn generate(&self, seed: Option<u64>) -> Board {
+    self.generate_seeded(seed.unwrap_or_else(|| fastrand::u64(..)))
+  }
+
+  /// Deal the board for the seed. This is all there is without `std`,
+  /// since there's nowhere to get a random seed from.
+  pub fn generate_seeded(&self, seed: u64) -> Board {
+    match self {
+      Generator::Shuffled => shuffled_random_seeded(seed),
+      Generator::Smartish => smartish_random_seeded(seed),
+    }
+  }
+}
+
+impl Display for Generator {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+impl FromStr for Generator {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Generator::ALL.into_iter().find(|g| g.name() == s).ok_or(())
+  }
+}
@@ -3,11 +3,14 @@ use itertools::Itertools;
 
 use crate::{Board, Card, MinorSuit, Suit};
 
+/// Like [`shuffled_random_seeded`], but picks a seed if there isn't one.
+#[cfg(feature = "std")]
 pub fn shuffled_random(seed: Option<u64>) -> Board {
-  let mut rng = match seed {
-    Some(seed) => Rng::with_seed(seed),
-    None => Rng::new(),
-  };
+  shuffled_random_seeded(seed.unwrap_or_else(|| fastrand::u64(..)))
+}
+
+pub fn shuffled_random_seeded(seed: u64) -> Board {
+  let mut rng = Rng::with_seed(seed);
 
   let mut all_the_cards = {
     // Skip aces
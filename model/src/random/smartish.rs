@@ -1,4 +1,5 @@
-use std::array;
+use alloc::{vec, vec::Vec};
+use core::array;
 
 use fastrand::Rng;
 use itertools::Itertools;
@@ -12,17 +13,14 @@ struct BoardGenerator {
 }
 
 impl BoardGenerator {
-  fn new(seed: Option<u64>) -> Self {
-    let mut rng = match seed {
-      Some(seed) => Rng::with_seed(seed),
-      None => Rng::new(),
-    };
+  fn new(seed: u64) -> Self {
+    let mut rng = Rng::with_seed(seed);
 
     let middle_arcana = rng.u8(0..=Card::MAJOR_ARCANA_MAX);
-    let mut left_arcana = (0..middle_arcana)
+    let left_arcana = (0..middle_arcana)
       .map(|idx| Card::new(Suit::MajorArcana, idx))
       .collect_vec();
-    let mut right_arcana = (middle_arcana..=Card::MAJOR_ARCANA_MAX)
+    let right_arcana = (middle_arcana..=Card::MAJOR_ARCANA_MAX)
       .map(|idx| Card::new(Suit::MajorArcana, idx))
       .collect_vec();
     // if rng.f32() < 0.5 {
@@ -64,8 +62,7 @@ impl BoardGenerator {
         self.rng.shuffle(&mut cil);
         cil
       };
-      for idx in 0..self.board_columns.len() {
-        let idx = column_idx_lookup[idx];
+      for idx in column_idx_lookup {
         let col = &mut self.board_columns[idx];
         let Some(last_card) = col.last() else {
           continue;
@@ -208,7 +205,13 @@ impl BoardGenerator {
   }
 }
 
+/// Like [`smartish_random_seeded`], but picks a seed if there isn't one.
+#[cfg(feature = "std")]
 pub fn smartish_random(seed: Option<u64>) -> Board {
+  smartish_random_seeded(seed.unwrap_or_else(|| fastrand::u64(..)))
+}
+
+pub fn smartish_random_seeded(seed: u64) -> Board {
   let mut generator = BoardGenerator::new(seed);
 
   while generator.any_source_stacks_left() {
@@ -1,5 +1,4 @@
-use ahash::{AHashMap, AHashSet};
-use backtrack::problem::Scope;
+use ahash::AHashSet;
 use itertools::Itertools;
 
 use crate::{Board, BoardZone};
@@ -37,8 +36,6 @@ struct Solver {
 
 impl Solver {
   fn take_step(&mut self) -> bool {
-    let depth = self.steps.len();
-
     let step = self.steps.last_mut().unwrap();
     let mut board2 = step.state.clone();
 
@@ -48,28 +45,17 @@ impl Solver {
       Ok(()) => {
         // hey! this was a valid move
         board2.check_automove_cards();
-        if board_is_dead(&board2) {
-          println!("#{}: board is dead", depth);
-          None
-        } else if !self.seen_states.insert(board2.clone()) {
-          // println!("#{}: this board has been seen before", depth);
+        if board_is_dead(&board2) || !self.seen_states.insert(board2.clone()) {
           None
         } else {
           if board2.is_solved() {
             return true;
           }
           self.seen_states.insert(board2.clone());
-          // println!(
-          //   "#{}: seeking from {:?} -> {:?}",
-          //   depth, &step.trying_src, &step.trying_dst
-          // );
           Some(Step::new(board2))
         }
       }
-      Err(_) => {
-        // println!("#{}: why did we even try that move", depth);
-        None
-      }
+      Err(_) => None,
     };
 
     if let Some(next_step) = next_step {
@@ -78,7 +64,6 @@ impl Solver {
       // Try to advance this step
       if step.trying_zones.next().is_none() {
         // This line of inquiry is thru with
-        println!("#{}: tried all moves from this position", depth);
         self.steps.pop();
       }
     }
@@ -160,6 +145,15 @@ impl Iterator for ZonesIter {
 }
 
 pub fn try_solve(board: &Board) -> Option<Vec<(BoardZone, BoardZone)>> {
+  try_solve_while(board, || true)
+}
+
+/// Like [`try_solve`], but gives up and returns `None` as soon as
+/// `keep_going` returns false. It gets checked before every step.
+pub fn try_solve_while(
+  board: &Board,
+  mut keep_going: impl FnMut() -> bool,
+) -> Option<Vec<(BoardZone, BoardZone)>> {
   let mut board2 = board.clone();
   board2.check_automove_cards();
   let mut solver = Solver {
@@ -167,7 +161,7 @@ pub fn try_solve(board: &Board) -> Option<Vec<(BoardZone, BoardZone)>> {
     steps: vec![Step::new(board2)],
   };
 
-  while !solver.steps.is_empty() {
+  while !solver.steps.is_empty() && keep_going() {
     let success = solver.take_step();
     if success {
       // woooooohoooo!
@@ -0,0 +1,330 @@
+//! Player statistics, kept in a little text file between sessions.
+//!
+//! The file has one record per line:
+//! `<generator> <seed or *> <played> <won> <abandoned> <streak>
+//! <best streak> <best time in ms or -> <fewest moves or ->`.
+//! The `*` line for a generator totals every seed dealt by it.
+
+use std::{
+  collections::BTreeMap,
+  fmt::Display,
+  fs, io,
+  path::{Path, PathBuf},
+  time::Duration,
+};
+
+use getset::CopyGetters;
+use itertools::Itertools;
+
+use crate::{
+  game::{Game, GameOutcome},
+  random::Generator,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+  records: BTreeMap<(Generator, Option<u64>), Record>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct Record {
+  played: u32,
+  won: u32,
+  abandoned: u32,
+  /// How many games in a row have been won, up to now.
+  streak: u32,
+  best_streak: u32,
+  best_time: Option<Duration>,
+  fewest_moves: Option<u32>,
+}
+
+impl Stats {
+  /// Where the stats file lives, if the platform has a data directory.
+  pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("teletarot").join("stats.txt"))
+  }
+
+  /// Load stats from the given file. A missing file is just empty stats,
+  /// and lines that don't make sense are skipped.
+  pub fn load(path: &Path) -> io::Result<Self> {
+    let text = match fs::read_to_string(path) {
+      Ok(it) => it,
+      Err(ono) if ono.kind() == io::ErrorKind::NotFound => {
+        return Ok(Self::default())
+      }
+      Err(ono) => return Err(ono),
+    };
+
+    let records = text
+      .lines()
+      .filter(|line| !line.starts_with('#'))
+      .filter_map(Record::parse_line)
+      .collect();
+    Ok(Self { records })
+  }
+
+  /// Load the stats at the default path, or empty stats if there isn't one.
+  pub fn load_default() -> io::Result<Self> {
+    match Self::default_path() {
+      Some(path) => Self::load(&path),
+      None => Ok(Self::default()),
+    }
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let lines = self
+      .records
+      .iter()
+      .map(|((generator, seed), record)| {
+        let seed = match seed {
+          Some(it) => it.to_string(),
+          None => "*".to_string(),
+        };
+        format!("{} {} {}", generator, seed, record.to_line())
+      })
+      .join("\n");
+    fs::write(path, format!("# teletarot stats\n{}\n", lines))
+  }
+
+  /// Load the stats at the default path, record the game, and save them again.
+  ///
+  /// Re-reading the file each time means two controllers open at once
+  /// won't clobber each other's games.
+  pub fn record_to_default_path(game: &Game) -> io::Result<()> {
+    let Some(path) = Self::default_path() else {
+      return Ok(());
+    };
+    let mut stats = Self::load(&path)?;
+    stats.record(game.generator(), game.seed(), game.outcome());
+    stats.save(&path)
+  }
+
+  pub fn record(
+    &mut self,
+    generator: Generator,
+    seed: u64,
+    outcome: GameOutcome,
+  ) {
+    for key in [(generator, None), (generator, Some(seed))] {
+      self.records.entry(key).or_default().record(outcome);
+    }
+  }
+
+  /// The totals for everything dealt by this generator.
+  pub fn for_generator(&self, generator: Generator) -> Option<&Record> {
+    self.records.get(&(generator, None))
+  }
+
+  pub fn for_seed(&self, generator: Generator, seed: u64) -> Option<&Record> {
+    self.records.get(&(generator, Some(seed)))
+  }
+
+  /// How it's gone with every generator, then with the deal being played,
+  /// for showing to the player.
+  pub fn summary(&self, generator: Generator, seed: u64) -> String {
+    let empty = Record::default();
+    let mut text = String::new();
+    for generator in Generator::ALL {
+      let record = self.for_generator(generator).unwrap_or(&empty);
+      text += &format!("All {} games:\n{}\n\n", generator, record);
+    }
+    let record = self.for_seed(generator, seed).unwrap_or(&empty);
+    text += &format!("This deal ({} seed {}):\n{}", generator, seed, record);
+    text
+  }
+}
+
+impl Record {
+  fn record(&mut self, outcome: GameOutcome) {
+    self.played += 1;
+    match outcome {
+      GameOutcome::Won { time, moves } => {
+        self.won += 1;
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+        self.best_time = Some(match self.best_time {
+          Some(best) => best.min(time),
+          None => time,
+        });
+        self.fewest_moves = Some(match self.fewest_moves {
+          Some(best) => best.min(moves),
+          None => moves,
+        });
+      }
+      GameOutcome::Abandoned => {
+        self.abandoned += 1;
+        self.streak = 0;
+      }
+    }
+  }
+
+  fn to_line(&self) -> String {
+    let best_time = match self.best_time {
+      Some(it) => it.as_millis().to_string(),
+      None => "-".to_string(),
+    };
+    let fewest_moves = match self.fewest_moves {
+      Some(it) => it.to_string(),
+      None => "-".to_string(),
+    };
+    format!(
+      "{} {} {} {} {} {} {}",
+      self.played,
+      self.won,
+      self.abandoned,
+      self.streak,
+      self.best_streak,
+      best_time,
+      fewest_moves
+    )
+  }
+
+  fn parse_line(line: &str) -> Option<((Generator, Option<u64>), Record)> {
+    let mut words = line.split_whitespace();
+    let generator = words.next()?.parse().ok()?;
+    let seed = match words.next()? {
+      "*" => None,
+      it => Some(it.parse().ok()?),
+    };
+
+    let mut number = || words.next()?.parse::<u32>().ok();
+    let played = number()?;
+    let won = number()?;
+    let abandoned = number()?;
+    let streak = number()?;
+    let best_streak = number()?;
+
+    let best_time = match words.next()? {
+      "-" => None,
+      it => Some(Duration::from_millis(it.parse().ok()?)),
+    };
+    let fewest_moves = match words.next()? {
+      "-" => None,
+      it => Some(it.parse().ok()?),
+    };
+
+    let record = Record {
+      played,
+      won,
+      abandoned,
+      streak,
+      best_streak,
+      best_time,
+      fewest_moves,
+    };
+    Some(((generator, seed), record))
+  }
+}
+
+impl Display for Record {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    writeln!(
+      f,
+      "played {}, won {}, abandoned {}",
+      self.played, self.won, self.abandoned
+    )?;
+    writeln!(f, "win streak {} (best {})", self.streak, self.best_streak)?;
+    match self.best_time {
+      Some(it) => write!(f, "best time {}", format_duration(it))?,
+      None => f.write_str("best time --:--")?,
+    }
+    match self.fewest_moves {
+      Some(it) => write!(f, ", fewest moves {}", it),
+      None => f.write_str(", fewest moves -"),
+    }
+  }
+}
+
+/// Format a duration like `mm:ss`, or `h:mm:ss` if it's a long one.
+pub fn format_duration(duration: Duration) -> String {
+  let secs = duration.as_secs();
+  let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+  if h > 0 {
+    format!("{}:{:02}:{:02}", h, m, s)
+  } else {
+    format!("{:02}:{:02}", m, s)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn won(secs: u64, moves: u32) -> GameOutcome {
+    GameOutcome::Won {
+      time: Duration::from_secs(secs),
+      moves,
+    }
+  }
+
+  #[test]
+  fn records_round_trip_through_lines() {
+    let mut record = Record::default();
+    record.record(won(95, 120));
+    record.record(GameOutcome::Abandoned);
+    for (seed, written) in [(Some(42), "42"), (None, "*")] {
+      let line = format!("smartish {} {}", written, record.to_line());
+      let key = (Generator::Smartish, seed);
+      assert_eq!(Record::parse_line(&line), Some((key, record.clone())));
+    }
+    assert_eq!(
+      Record::parse_line("shuffled * 0 0 0 0 0 - -"),
+      Some(((Generator::Shuffled, None), Record::default()))
+    );
+  }
+
+  #[test]
+  fn malformed_lines_are_skipped() {
+    for line in [
+      "",
+      "smartish 42 1 1 0 1 1",
+      "smartish 42 1 1 0 1 1 95000",
+      "bogus * 1 1 0 1 1 - -",
+      "smartish x 1 1 0 1 1 - -",
+      "smartish 42 1 one 0 1 1 - -",
+      "smartish 42 1 1 0 1 1 fast -",
+    ] {
+      assert_eq!(Record::parse_line(line), None, "{:?}", line);
+    }
+
+    let path = std::env::temp_dir()
+      .join(format!("teletarot-stats-{}.txt", std::process::id()));
+    let text = "# teletarot stats\nsmartish * 1 1 0 1 1 95000 120\n\
+                smartish 42 1 1\n\nshuffled 7 1 0 1 0 0 - -\n";
+    fs::write(&path, text).unwrap();
+    let stats = Stats::load(&path);
+    let _ = fs::remove_file(&path);
+    let stats = stats.unwrap();
+    assert_eq!(stats.records.len(), 2);
+    assert!(stats.for_generator(Generator::Smartish).is_some());
+    assert!(stats.for_seed(Generator::Smartish, 42).is_none());
+    assert!(stats.for_seed(Generator::Shuffled, 7).is_some());
+  }
+
+  #[test]
+  fn wins_and_losses_update_streaks_and_bests() {
+    let mut record = Record::default();
+    record.record(won(90, 100));
+    record.record(won(60, 130));
+    assert_eq!((record.streak(), record.best_streak()), (2, 2));
+    assert_eq!(record.best_time(), Some(Duration::from_secs(60)));
+    assert_eq!(record.fewest_moves(), Some(100));
+
+    record.record(GameOutcome::Abandoned);
+    assert_eq!((record.streak(), record.best_streak()), (0, 2));
+    assert_eq!(record.best_time(), Some(Duration::from_secs(60)));
+
+    record.record(won(120, 80));
+    assert_eq!((record.streak(), record.best_streak()), (1, 2));
+    assert_eq!(record.best_time(), Some(Duration::from_secs(60)));
+    assert_eq!(record.fewest_moves(), Some(80));
+    assert_eq!(
+      (record.played(), record.won(), record.abandoned()),
+      (4, 3, 1)
+    );
+  }
+}
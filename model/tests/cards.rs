@@ -0,0 +1,15 @@
+use teletarot_model::{Board, Card};
+
+#[test]
+fn cards_read_back_how_they_display() {
+  for card in Board::all_cards() {
+    assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+  }
+}
+
+#[test]
+fn cards_are_only_read_how_they_display() {
+  for s in ["", "A", "+5A", "-0A", "05A", "00A", "22A", "1c", "Xc", "5x"] {
+    assert!(s.parse::<Card>().is_err(), "{:?} was read as a card", s);
+  }
+}
@@ -0,0 +1,67 @@
+use teletarot_model::{
+  player::{play_game, GreedyPlayer, Outcome, SolverPlayer, Tournament},
+  random::Generator,
+  solver,
+};
+
+/// A smartish deal the solver can win.
+const SEED: u64 = 45;
+
+#[test]
+fn the_solver_wins_a_deal_it_can_solve() {
+  let board = Generator::Smartish.generate_seeded(SEED);
+  let mut automoved = board.clone();
+  automoved.check_automove_cards();
+  let soln = solver::try_solve(&automoved).expect("the deal can be solved");
+
+  let mut player = SolverPlayer::new(10_000);
+  let outcome = play_game(&mut player, board, 1000);
+  assert_eq!(
+    outcome,
+    Outcome {
+      won: true,
+      moves: soln.len() as u32
+    }
+  );
+}
+
+#[test]
+fn running_out_of_moves_loses() {
+  let board = Generator::Smartish.generate_seeded(SEED);
+  let mut player = SolverPlayer::new(10_000);
+  let outcome = play_game(&mut player, board, 1);
+  assert_eq!(
+    outcome,
+    Outcome {
+      won: false,
+      moves: 1
+    }
+  );
+}
+
+#[test]
+fn standings_add_up_the_games_played() {
+  let tournament = Tournament::new((0..6).collect(), 1000);
+  let mut outcomes = Vec::new();
+  let standings = tournament.run(&mut GreedyPlayer::new(), |gen, _, it| {
+    outcomes.push((gen, it))
+  });
+  assert_eq!(standings.len(), Generator::ALL.len());
+
+  for (generator, standing) in Generator::ALL.iter().zip(&standings) {
+    let played = outcomes
+      .iter()
+      .filter(|(gen, _)| gen == generator)
+      .map(|(_, it)| *it)
+      .collect::<Vec<_>>();
+    let won = played.iter().filter(|it| it.won).collect::<Vec<_>>();
+    assert_eq!(standing.played(), 6);
+    assert_eq!(standing.won() as usize, won.len());
+
+    let total_moves = won.iter().map(|it| it.moves as u64).sum::<u64>();
+    assert_eq!(standing.total_moves(), total_moves);
+    let average =
+      (!won.is_empty()).then(|| total_moves as f64 / won.len() as f64);
+    assert_eq!(standing.average_moves(), average);
+  }
+}
@@ -0,0 +1,72 @@
+use serde_json::{json, Value};
+use teletarot_model::{random::Generator, Board, BoardZone, CardMoveError};
+
+fn dealt() -> Board {
+  let mut board = Generator::Smartish.generate_seeded(2024);
+  board.check_automove_cards();
+  board
+}
+
+#[test]
+fn boards_round_trip() {
+  let board = dealt();
+  let text = serde_json::to_string(&board).unwrap();
+  assert_eq!(serde_json::from_str::<Board>(&text).unwrap(), board);
+}
+
+#[test]
+fn boards_are_checked_as_theyre_read() {
+  let mut value = serde_json::to_value(dealt()).unwrap();
+  // Swap one card for a copy of another
+  let copy = value["columns"][1][0].clone();
+  value["columns"][0][0] = copy.clone();
+  let ono = serde_json::from_value::<Board>(value).unwrap_err();
+  let message = format!("{} is there 2 times", copy.as_str().unwrap());
+  assert!(ono.to_string().contains(&message), "{}", ono);
+}
+
+#[test]
+fn zones_and_errors_are_written_how_theyre_typed() {
+  let zones = BoardZone::all().collect::<Vec<_>>();
+  let value = serde_json::to_value(&zones).unwrap();
+  assert_eq!(value[3], json!("3"));
+  assert_eq!(value[11], json!("s"));
+  assert_eq!(
+    serde_json::from_value::<Vec<BoardZone>>(value).unwrap(),
+    zones
+  );
+
+  let ono = CardMoveError::WriteOnlySource {
+    zone: BoardZone::MajorFoundation,
+  };
+  let value = serde_json::to_value(&ono).unwrap();
+  assert_eq!(value, json!({ "kind": ono.kind(), "zone": "A" }));
+  assert_eq!(serde_json::from_value::<CardMoveError>(value).unwrap(), ono);
+  assert!(serde_json::from_value::<CardMoveError>(Value::Null).is_err());
+
+  let value = serde_json::to_value(ono.explained()).unwrap();
+  let message = ono.to_string();
+  assert_eq!(
+    value,
+    json!({ "kind": ono.kind(), "zone": "A", "message": message })
+  );
+}
+
+#[test]
+fn errors_say_where_the_card_came_from() {
+  let mut board = dealt();
+  let (src, dest) = BoardZone::all()
+    .flat_map(|src| BoardZone::all().map(move |dest| (src, dest)))
+    .find(|&(src, dest)| {
+      matches!(
+        board.clone().move_card(src, dest, true),
+        Err(CardMoveError::CannotStack { .. })
+      )
+    })
+    .expect("something can't stack");
+  let ono = board.move_card(src, dest, true).unwrap_err();
+  let value = serde_json::to_value(&ono).unwrap();
+  assert_eq!(value["src"], json!(src.short_name()));
+  assert_eq!(value["dest"], json!(dest.short_name()));
+  assert!(ono.to_string().contains(&src.to_string()), "{}", ono);
+}
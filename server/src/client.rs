@@ -0,0 +1,148 @@
+//! A little blocking client for the server, for tests and bots.
+//!
+//! It only speaks enough HTTP to talk to this server.
+
+use std::{
+  io::{Read, Write},
+  net::{SocketAddr, TcpStream},
+};
+
+use serde_json::{json, Value};
+use teletarot_model::{random::Generator, BoardZone};
+use tungstenite::{Message, WebSocket};
+
+pub struct Client {
+  addr: SocketAddr,
+}
+
+/// A WebSocket watching one game. It yields the game every time it
+/// changes, and ends when the game's gone.
+pub struct Watcher {
+  socket: WebSocket<TcpStream>,
+}
+
+impl Client {
+  pub fn new(addr: SocketAddr) -> Self {
+    Self { addr }
+  }
+
+  pub fn games(&self) -> Result<Value, String> {
+    self.request("GET", "/games", None)
+  }
+
+  pub fn new_game(
+    &self,
+    seed: Option<u64>,
+    generator: Option<Generator>,
+  ) -> Result<Value, String> {
+    let body = json!({
+      "seed": seed,
+      "generator": generator.map(|it| it.name()),
+    });
+    self.request("POST", "/games", Some(&body))
+  }
+
+  pub fn game(&self, id: u64) -> Result<Value, String> {
+    self.request("GET", &format!("/games/{}", id), None)
+  }
+
+  pub fn delete_game(&self, id: u64) -> Result<Value, String> {
+    self.request("DELETE", &format!("/games/{}", id), None)
+  }
+
+  pub fn move_card(
+    &self,
+    id: u64,
+    src: BoardZone,
+    dst: BoardZone,
+  ) -> Result<Value, String> {
+    let body = json!({ "from": src.short_name(), "to": dst.short_name() });
+    self.request("POST", &format!("/games/{}/moves", id), Some(&body))
+  }
+
+  pub fn undo(&self, id: u64) -> Result<Value, String> {
+    self.request("POST", &format!("/games/{}/undo", id), None)
+  }
+
+  pub fn hint(&self, id: u64) -> Result<Value, String> {
+    self.request("GET", &format!("/games/{}/hint", id), None)
+  }
+
+  /// Start watching a game. The first message is the game as it is.
+  pub fn watch(&self, id: u64) -> Result<Watcher, String> {
+    let stream =
+      TcpStream::connect(self.addr).map_err(|ono| ono.to_string())?;
+    let url = format!("ws://{}/games/{}/ws", self.addr, id);
+    let (socket, _) =
+      tungstenite::client(url, stream).map_err(|ono| ono.to_string())?;
+    Ok(Watcher { socket })
+  }
+
+  /// Send a request and read the JSON that comes back. If it isn't a 2xx,
+  /// the `Err` is the status and the error's kind and message, like
+  /// `404 not-found: there's nothing here`.
+  pub fn request(
+    &self,
+    method: &str,
+    path: &str,
+    body: Option<&Value>,
+  ) -> Result<Value, String> {
+    let body = body.map(|it| it.to_string()).unwrap_or_default();
+    let mut stream =
+      TcpStream::connect(self.addr).map_err(|ono| ono.to_string())?;
+    write!(
+      stream,
+      "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\
+       Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+      method,
+      path,
+      self.addr,
+      body.len(),
+      body
+    )
+    .map_err(|ono| ono.to_string())?;
+    let mut response = String::new();
+    stream
+      .read_to_string(&mut response)
+      .map_err(|ono| ono.to_string())?;
+
+    let (head, body) = response
+      .split_once("\r\n\r\n")
+      .ok_or_else(|| "the response didn't have a body".to_string())?;
+    let status = head
+      .split_whitespace()
+      .nth(1)
+      .and_then(|it| it.parse::<u16>().ok())
+      .ok_or_else(|| format!("couldn't read the status from {:?}", head))?;
+    let value =
+      serde_json::from_str::<Value>(body).map_err(|ono| ono.to_string())?;
+    if (200..300).contains(&status) {
+      Ok(value)
+    } else {
+      let error = &value["error"];
+      match (error["kind"].as_str(), error["message"].as_str()) {
+        (Some(kind), Some(message)) => {
+          Err(format!("{} {}: {}", status, kind, message))
+        }
+        _ => Err(format!("{}: {}", status, value)),
+      }
+    }
+  }
+}
+
+impl Iterator for Watcher {
+  type Item = Result<Value, String>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let text = match self.socket.read() {
+        Ok(Message::Text(it)) => it,
+        Ok(Message::Close(_)) => return None,
+        Ok(_) => continue,
+        Err(tungstenite::Error::ConnectionClosed) => return None,
+        Err(ono) => return Some(Err(ono.to_string())),
+      };
+      return Some(serde_json::from_str(&text).map_err(|ono| ono.to_string()));
+    }
+  }
+}
@@ -0,0 +1,24 @@
+use serde_json::{json, Value};
+use teletarot_model::{game::Game, BoardZone, CardMoveError};
+
+/// Everything about a game: its id, then the model's summary of it.
+pub fn game_json(id: u64, game: &Game) -> Value {
+  let mut value =
+    serde_json::to_value(game.summary()).expect("games can be JSON");
+  value["id"] = json!(id);
+  value
+}
+
+pub fn move_json(src: BoardZone, dst: BoardZone) -> Value {
+  json!({ "from": src, "to": dst })
+}
+
+/// `kind` is for programs and `message` is for people.
+pub fn error_json(kind: &str, message: &str) -> Value {
+  json!({ "error": { "kind": kind, "message": message } })
+}
+
+/// Like [`error_json`], but with what the move error was about too.
+pub fn move_error_json(ono: &CardMoveError) -> Value {
+  json!({ "error": ono.explained() })
+}
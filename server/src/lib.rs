@@ -0,0 +1,405 @@
+//! Host teletarot games over HTTP, with a WebSocket for each game that
+//! gets sent the game whenever it changes.
+//!
+//! Every body in and out is JSON. Zones are written as
+//! [`BoardZone::short_name`]: column numbers, `s`, `a` and `A`.
+//!
+//! - `GET /games` lists every game.
+//! - `POST /games` deals a new one. The body can have a `seed` and a
+//!   `generator`, and both are optional.
+//! - `GET /games/<id>` is the game as it is.
+//! - `DELETE /games/<id>` gets rid of it.
+//! - `POST /games/<id>/moves` with `{"from": "0", "to": "5"}` moves a card,
+//!   then moves whatever can go to the foundations.
+//! - `POST /games/<id>/undo` takes the last move back.
+//! - `GET /games/<id>/hint` asks the solver for the next move. It's `null`
+//!   if the solver can't find one in time.
+//! - `GET /games/<id>/ws` upgrades to a WebSocket, which gets the game
+//!   straight away and again after every change.
+//!
+//! Errors come back as `{"error": {"kind": ..., "message": ...}}`. Kinds are
+//! kebab-case, like `not-found`, `bad-request`, `nothing-to-undo`, or a
+//! move that didn't work's [`CardMoveError::kind`], like `cannot-stack`.
+//!
+//! To test against it without a separate process, bind a [`Server`] to
+//! port 0, run it on a thread, and point a [`client::Client`] at its
+//! [`Server::addr`].
+//!
+//! [`CardMoveError::kind`]: teletarot_model::CardMoveError::kind
+
+pub mod client;
+mod json;
+
+use std::{
+  collections::BTreeMap,
+  net::{SocketAddr, ToSocketAddrs},
+  sync::{
+    mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    Arc, Mutex,
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+use json::{error_json, game_json, move_error_json, move_json};
+use serde_json::{json, Value};
+use teletarot_model::{game::Game, random::Generator, solver, BoardZone};
+use tiny_http::{Header, Method, ReadWrite, Request, Response, StatusCode};
+use tungstenite::{
+  handshake::derive_accept_key, protocol::Role, Message, WebSocket,
+};
+
+pub struct Server {
+  http: tiny_http::Server,
+  games: Arc<Mutex<Games>>,
+}
+
+/// Every game being played, by id.
+#[derive(Default)]
+struct Games {
+  next_id: u64,
+  hosted: BTreeMap<u64, Hosted>,
+  /// How many WebSockets are open, on any game.
+  watching: usize,
+}
+
+struct Hosted {
+  game: Game,
+  /// A sender for each WebSocket watching the game.
+  watchers: Vec<Sender<String>>,
+}
+
+/// A status code and a body to send back.
+type Reply = (u16, Value);
+
+impl Server {
+  /// How long the solver gets to think of a hint.
+  pub const HINT_TIME: Duration = Duration::from_secs(5);
+  /// How many requests get answered at once. A hint keeps one busy for up
+  /// to [`Server::HINT_TIME`].
+  pub const WORKERS: usize = 8;
+  /// How many WebSockets can be open at once, since each has its own thread.
+  pub const MAX_WATCHERS: usize = 64;
+  /// How long a WebSocket can go quiet before it's pinged, to check it's
+  /// still there.
+  pub const PING_TIME: Duration = Duration::from_secs(10);
+
+  pub fn bind(addr: impl ToSocketAddrs) -> Result<Self, String> {
+    let http = tiny_http::Server::http(addr).map_err(|ono| ono.to_string())?;
+    Ok(Self {
+      http,
+      games: Default::default(),
+    })
+  }
+
+  /// Where it's listening, like if it was bound to port 0.
+  pub fn addr(&self) -> SocketAddr {
+    self
+      .http
+      .server_addr()
+      .to_ip()
+      .expect("the server was bound to an IP address")
+  }
+
+  /// Answer requests forever, [`Server::WORKERS`] at a time.
+  pub fn run(self) {
+    let http = Arc::new(self.http);
+    let workers = (0..Self::WORKERS)
+      .map(|_| {
+        let http = http.clone();
+        let games = self.games.clone();
+        thread::spawn(move || {
+          for request in http.incoming_requests() {
+            handle(&games, request);
+          }
+        })
+      })
+      .collect::<Vec<_>>();
+    for worker in workers {
+      let _ = worker.join();
+    }
+  }
+}
+
+fn handle(games: &Arc<Mutex<Games>>, mut request: Request) {
+  let path = request
+    .url()
+    .split('?')
+    .next()
+    .unwrap_or_default()
+    .to_string();
+  let segments = path
+    .split('/')
+    .filter(|it| !it.is_empty())
+    .collect::<Vec<_>>();
+  let method = request.method().clone();
+
+  let reply = match (&method, &segments[..]) {
+    (Method::Get, ["games"]) => list_games(games),
+    (Method::Post, ["games"]) => read_body(&mut request)
+      .and_then(|body| new_game(games, &body))
+      .unwrap_or_else(|ono| ono),
+    (method, ["games", id, rest @ ..]) => {
+      let Ok(id) = id.parse::<u64>() else {
+        return respond(request, not_found());
+      };
+      match (method, rest) {
+        (Method::Get, []) => with_game(games, id, |_| Ok(())),
+        (Method::Delete, []) => delete_game(games, id),
+        (Method::Post, ["moves"]) => read_body(&mut request)
+          .and_then(|body| move_card(games, id, &body))
+          .unwrap_or_else(|ono| ono),
+        (Method::Post, ["undo"]) => with_game(games, id, |game| {
+          if game.undo() {
+            Ok(())
+          } else {
+            Err((409, error_json("nothing-to-undo", "nothing to undo")))
+          }
+        }),
+        (Method::Get, ["hint"]) => hint(games, id),
+        (Method::Get, ["ws"]) => return watch(games, id, request),
+        _ => not_found(),
+      }
+    }
+    _ => not_found(),
+  };
+  respond(request, reply);
+}
+
+fn respond(request: Request, (status, body): Reply) {
+  let response = Response::from_string(body.to_string())
+    .with_status_code(status)
+    .with_header(header("Content-Type", "application/json"));
+  // If they've hung up there's no one to tell
+  let _ = request.respond(response);
+}
+
+fn header(name: &str, value: &str) -> Header {
+  Header::from_bytes(name, value).expect("headers are ascii")
+}
+
+fn not_found() -> Reply {
+  (404, error_json("not-found", "there's nothing here"))
+}
+
+fn read_body(request: &mut Request) -> Result<Value, Reply> {
+  let bad = |message: String| (400, error_json("bad-request", &message));
+  let mut body = String::new();
+  request
+    .as_reader()
+    .read_to_string(&mut body)
+    .map_err(|ono| bad(ono.to_string()))?;
+  if body.trim().is_empty() {
+    return Ok(json!({}));
+  }
+  serde_json::from_str(&body).map_err(|ono| bad(ono.to_string()))
+}
+
+fn list_games(games: &Mutex<Games>) -> Reply {
+  let games = games.lock().unwrap();
+  let list = games
+    .hosted
+    .iter()
+    .map(|(id, hosted)| game_json(*id, &hosted.game))
+    .collect::<Vec<_>>();
+  (200, json!({ "games": list }))
+}
+
+fn new_game(games: &Mutex<Games>, body: &Value) -> Result<Reply, Reply> {
+  let bad = |message: &str| (400, error_json("bad-request", message));
+  let seed = match &body["seed"] {
+    Value::Null => None,
+    seed => Some(seed.as_u64().ok_or_else(|| bad("`seed` is a number"))?),
+  };
+  let generator = match &body["generator"] {
+    Value::Null => Generator::Smartish,
+    generator => generator
+      .as_str()
+      .and_then(|it| it.parse().ok())
+      .ok_or_else(|| bad("`generator` is shuffled or smartish"))?,
+  };
+
+  let mut game = Game::new(generator, seed);
+  game.check_automove_cards();
+  let mut games = games.lock().unwrap();
+  games.next_id += 1;
+  let id = games.next_id;
+  let reply = game_json(id, &game);
+  let watchers = Vec::new();
+  games.hosted.insert(id, Hosted { game, watchers });
+  Ok((201, reply))
+}
+
+fn delete_game(games: &Mutex<Games>, id: u64) -> Reply {
+  // Dropping it hangs up on its watchers
+  match games.lock().unwrap().hosted.remove(&id) {
+    Some(_) => (200, json!({})),
+    None => not_found(),
+  }
+}
+
+fn move_card(
+  games: &Mutex<Games>,
+  id: u64,
+  body: &Value,
+) -> Result<Reply, Reply> {
+  let zone = |key: &str| {
+    body[key]
+      .as_str()
+      .and_then(|it| it.parse::<BoardZone>().ok())
+      .ok_or_else(|| {
+        let message =
+          format!("`{}` should be a zone, like \"3\" or \"s\"", key);
+        (400, error_json("bad-request", &message))
+      })
+  };
+  let (src, dst) = (zone("from")?, zone("to")?);
+  Ok(with_game(games, id, |game| {
+    game
+      .move_card(src, dst)
+      .map_err(|ono| (422, move_error_json(&ono)))?;
+    game.check_automove_cards();
+    Ok(())
+  }))
+}
+
+/// Do something to a game and reply with how it is after. If it worked,
+/// let everyone watching know.
+fn with_game(
+  games: &Mutex<Games>,
+  id: u64,
+  change: impl FnOnce(&mut Game) -> Result<(), Reply>,
+) -> Reply {
+  let mut games = games.lock().unwrap();
+  let Some(hosted) = games.hosted.get_mut(&id) else {
+    return not_found();
+  };
+  let before = hosted.game.board().clone();
+  if let Err(ono) = change(&mut hosted.game) {
+    return ono;
+  }
+  let reply = game_json(id, &hosted.game);
+  if *hosted.game.board() != before {
+    let text = reply.to_string();
+    hosted.watchers.retain(|tx| tx.send(text.clone()).is_ok());
+  }
+  (200, reply)
+}
+
+fn hint(games: &Mutex<Games>, id: u64) -> Reply {
+  // Don't keep everyone else waiting while the solver thinks
+  let board = match games.lock().unwrap().hosted.get(&id) {
+    Some(hosted) => hosted.game.board().clone(),
+    None => return not_found(),
+  };
+  let started = Instant::now();
+  let soln =
+    solver::try_solve_while(&board, || started.elapsed() < Server::HINT_TIME);
+  let hint = soln
+    .and_then(|soln| soln.first().copied())
+    .map(|(src, dst)| move_json(src, dst));
+  (200, json!({ "hint": hint }))
+}
+
+/// Upgrade to a WebSocket, and hand it to its own thread to send the game
+/// down whenever it changes.
+fn watch(games: &Arc<Mutex<Games>>, id: u64, request: Request) {
+  let key = request
+    .headers()
+    .iter()
+    .find(|it| it.field.equiv("Sec-WebSocket-Key"))
+    .map(|it| derive_accept_key(it.value.as_bytes()));
+  let Some(accept) = key else {
+    let message = "this is a WebSocket";
+    return respond(request, (400, error_json("bad-request", message)));
+  };
+
+  let (tx, rx) = mpsc::channel();
+  {
+    let mut games = games.lock().unwrap();
+    if games.watching >= Server::MAX_WATCHERS {
+      drop(games);
+      let message = "too many WebSockets are open already";
+      return respond(request, (503, error_json("too-many-watchers", message)));
+    }
+    let Some(hosted) = games.hosted.get_mut(&id) else {
+      drop(games);
+      return respond(request, not_found());
+    };
+    let _ = tx.send(game_json(id, &hosted.game).to_string());
+    hosted.watchers.push(tx);
+    games.watching += 1;
+  }
+
+  let response = Response::empty(StatusCode(101))
+    .with_header(header("Upgrade", "websocket"))
+    .with_header(header("Connection", "Upgrade"))
+    .with_header(header("Sec-WebSocket-Accept", &accept));
+  let stream = request.upgrade("websocket", response);
+  let socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+  let games = games.clone();
+  thread::spawn(move || {
+    stream_game(socket, rx);
+    games.lock().unwrap().watching -= 1;
+  });
+}
+
+type Socket = WebSocket<Box<dyn ReadWrite + Send>>;
+
+/// Send the game down the socket whenever it changes, until either side
+/// hangs up.
+fn stream_game(mut socket: Socket, rx: Receiver<String>) {
+  loop {
+    match rx.recv_timeout(Server::PING_TIME) {
+      Ok(text) => {
+        if socket.send(text.into()).is_err() {
+          return;
+        }
+      }
+      Err(RecvTimeoutError::Timeout) => match still_there(socket) {
+        Some(it) => socket = it,
+        None => return,
+      },
+      // The game's been deleted
+      Err(RecvTimeoutError::Disconnected) => break,
+    }
+  }
+  let _ = socket.close(None);
+  let _ = socket.flush();
+}
+
+/// Ping the socket and give the pong [`Server::PING_TIME`] to come back,
+/// handing the socket back if it did.
+///
+/// tiny_http doesn't let the stream have a read timeout, so the waiting
+/// happens on a thread of its own. If the other side's gone quiet without
+/// hanging up, that thread is left blocked until the connection dies, but
+/// the socket stops counting towards [`Server::MAX_WATCHERS`].
+fn still_there(socket: Socket) -> Option<Socket> {
+  let (tx, rx) = mpsc::channel();
+  thread::spawn(move || {
+    if let Some(socket) = wait_for_pong(socket) {
+      let _ = tx.send(socket);
+    }
+  });
+  rx.recv_timeout(Server::PING_TIME).ok()
+}
+
+/// Reading blocks, so this is the only time the socket gets read, and the
+/// only time a close from the other side gets noticed.
+fn wait_for_pong(mut socket: Socket) -> Option<Socket> {
+  socket.send(Message::Ping(Vec::new())).ok()?;
+  loop {
+    match socket.read() {
+      Ok(Message::Pong(_)) => return Some(socket),
+      // Reading it queued a close back, so send that off
+      Ok(Message::Close(_)) => {
+        let _ = socket.flush();
+        return None;
+      }
+      // They've got nothing to say that's worth hearing
+      Ok(_) => continue,
+      Err(_) => return None,
+    }
+  }
+}
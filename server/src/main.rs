@@ -0,0 +1,29 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use teletarot_server::Server;
+
+/// Host teletarot games over HTTP and WebSockets.
+///
+/// It only listens on localhost unless it's told otherwise.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+  /// The address to listen on.
+  #[arg(long, default_value = "127.0.0.1:7878")]
+  addr: String,
+}
+
+fn main() -> ExitCode {
+  let args = Args::parse();
+  let server = match Server::bind(&args.addr) {
+    Ok(it) => it,
+    Err(ono) => {
+      eprintln!("Couldn't listen on {}: {}", args.addr, ono);
+      return ExitCode::FAILURE;
+    }
+  };
+  println!("Listening on http://{}", server.addr());
+  server.run();
+  ExitCode::SUCCESS
+}
@@ -0,0 +1,131 @@
+use std::thread;
+
+use serde_json::{json, Value};
+use teletarot_model::{game::Game, random::Generator, BoardZone};
+use teletarot_server::{client::Client, Server};
+
+const SEED: u64 = 1234;
+
+/// A server on a port of its own, for just this test.
+fn start() -> Client {
+  let server = Server::bind("127.0.0.1:0").unwrap();
+  let client = Client::new(server.addr());
+  thread::spawn(move || server.run());
+  client
+}
+
+/// The same game the server deals for [`SEED`], to work out moves with.
+fn local_game() -> Game {
+  let mut game = Game::new(Generator::Shuffled, Some(SEED));
+  game.check_automove_cards();
+  game
+}
+
+fn new_game(client: &Client) -> u64 {
+  let game = client
+    .new_game(Some(SEED), Some(Generator::Shuffled))
+    .unwrap();
+  game["id"].as_u64().unwrap()
+}
+
+fn some_legal_move() -> (BoardZone, BoardZone) {
+  local_game().board().legal_moves()[0]
+}
+
+#[test]
+fn deals_the_game_asked_for() {
+  let client = start();
+  let game = client
+    .new_game(Some(SEED), Some(Generator::Shuffled))
+    .unwrap();
+  assert_eq!(game["seed"], json!(SEED));
+  assert_eq!(game["generator"], json!("shuffled"));
+  assert_eq!(game["moves"], json!(0));
+  assert_eq!(game["solved"], json!(false));
+
+  let local = local_game();
+  let columns = local
+    .board()
+    .columns()
+    .iter()
+    .map(|col| col.iter().map(|card| card.to_string()).collect::<Vec<_>>())
+    .collect::<Vec<_>>();
+  assert_eq!(game["board"]["columns"], json!(columns));
+
+  let id = game["id"].as_u64().unwrap();
+  assert_eq!(client.game(id).unwrap(), game);
+  assert_eq!(client.games().unwrap()["games"], json!([game]));
+}
+
+#[test]
+fn moves_cards() {
+  let client = start();
+  let id = new_game(&client);
+  let (src, dst) = some_legal_move();
+  let game = client.move_card(id, src, dst).unwrap();
+  assert_eq!(game["moves"], json!(1));
+}
+
+#[test]
+fn says_why_moves_dont_work() {
+  let client = start();
+  let id = new_game(&client);
+  let ono = client
+    .move_card(id, BoardZone::MajorFoundation, BoardZone::Column(0))
+    .unwrap_err();
+  assert!(ono.starts_with("422 write-only-source: "), "{}", ono);
+
+  let body = json!({ "from": "x", "to": "0" });
+  let path = format!("/games/{}/moves", id);
+  let ono = client.request("POST", &path, Some(&body)).unwrap_err();
+  assert!(ono.starts_with("400 bad-request: "), "{}", ono);
+
+  let ono = client.game(id + 1).unwrap_err();
+  assert!(ono.starts_with("404 not-found: "), "{}", ono);
+  assert_eq!(client.game(id).unwrap()["moves"], json!(0));
+}
+
+#[test]
+fn undoes_moves() {
+  let client = start();
+  let id = new_game(&client);
+  let ono = client.undo(id).unwrap_err();
+  assert!(ono.starts_with("409 nothing-to-undo: "), "{}", ono);
+
+  let dealt = client.game(id).unwrap();
+  let (src, dst) = some_legal_move();
+  client.move_card(id, src, dst).unwrap();
+  let game = client.undo(id).unwrap();
+  assert_eq!(game["undos"], json!(1));
+  assert_eq!(game["board"], dealt["board"]);
+}
+
+#[test]
+fn hints_at_moves_that_work() {
+  let client = start();
+  let id = new_game(&client);
+  let hint = client.hint(id).unwrap()["hint"].clone();
+  // It's allowed to give up, but if it didn't, the move has to work
+  if hint != Value::Null {
+    let zone = |key: &str| hint[key].as_str().unwrap().parse().unwrap();
+    client.move_card(id, zone("from"), zone("to")).unwrap();
+  }
+}
+
+#[test]
+fn streams_the_game_over_a_websocket() {
+  let client = start();
+  let id = new_game(&client);
+  let mut watcher = client.watch(id).unwrap();
+  let game = watcher.next().unwrap().unwrap();
+  assert_eq!(game["id"], json!(id));
+  assert_eq!(game["moves"], json!(0));
+
+  let (src, dst) = some_legal_move();
+  client.move_card(id, src, dst).unwrap();
+  let game = watcher.next().unwrap().unwrap();
+  assert_eq!(game["moves"], json!(1));
+
+  client.delete_game(id).unwrap();
+  assert!(watcher.next().is_none());
+}